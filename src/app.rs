@@ -1,15 +1,25 @@
+use crate::app::autosave::AutoSave;
+use crate::app::report::ReportState;
+use crate::app::today::TodayState;
+use crate::debug;
+use crate::task_list::TaskList;
 use crate::task_list_controller::TaskListController;
+mod autosave;
 mod events;
 mod render;
+mod report;
+mod today;
 
+use color_eyre::eyre::Result;
 use crossterm::event::KeyEvent;
 use ratatui::DefaultTerminal;
-use std::io;
+use std::path::Path;
 #[derive(Debug, Clone, Copy, Default)]
 enum AppMode {
     #[default]
     TaskList,
     TodayTasks,
+    Report,
 }
 
 #[derive(Debug, Default)]
@@ -18,14 +28,59 @@ pub struct App {
     task_detail_visible: bool,
     help_visible: bool,
     mode: AppMode,
+    today: TodayState,
+    report: ReportState,
+    autosave: AutoSave,
+    /// The error from the last autosave, if it failed and the changes have not been saved since.
+    save_error: Option<String>,
     exit: bool,
 }
 
 impl App {
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+    /// Creates the application, loading the saved task list from the data directory if one exists.
+    pub fn load() -> Result<App> {
+        let path = debug::get_task_list_path();
+        let mut task_list = match path.exists() {
+            true => TaskList::load(&path)?,
+            false => TaskList::default(),
+        };
+        task_list.roll_up = *debug::ROLL_UP;
+        task_list.calendar = *debug::CALENDAR;
+        Ok(App {
+            task_list_controller: TaskListController::new(task_list),
+            ..App::default()
+        })
+    }
+
+    /// Saves any unsaved changes to the task list to the data directory.
+    pub fn save(&mut self) -> Result<()> {
+        self.autosave
+            .flush(&mut self.task_list_controller.task_list)
+    }
+
+    /// Adds the tasks in a file to the task list.
+    pub fn import(&mut self, path: &Path) -> Result<()> {
+        self.task_list_controller.task_list.import(path)
+    }
+
+    /// Writes the task list to a file.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        self.task_list_controller.task_list.export(path)
+    }
+
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_events()?;
+            // Keep running when a save fails, as the save is tried again after the delay
+            match self
+                .autosave
+                .update(&mut self.task_list_controller.task_list)
+            {
+                Ok(()) if self.autosave.time_until_save().is_none() => self.save_error = None,
+                Ok(()) => {}
+                Err(error) => self.save_error = Some(error.to_string()),
+            }
         }
         Ok(())
     }
@@ -0,0 +1,67 @@
+use crate::debug;
+use crate::task_list::TaskList;
+use color_eyre::eyre::Result;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How long the task list has to go unchanged before it is saved.
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+/// The number of previous saves that are kept as backups.
+const AUTOSAVE_BACKUPS: usize = 5;
+
+/// Saves the task list to disk shortly after it changes, grouping rapid changes into one save.
+#[derive(Debug)]
+pub(crate) struct AutoSave {
+    /// The file that the task list is saved to.
+    path: PathBuf,
+    /// How long to wait after the last change before saving.
+    delay: Duration,
+    /// The number of backups to keep.
+    backups: usize,
+    /// The time of the most recent unsaved change, if there is one.
+    last_change: Option<Instant>,
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        AutoSave {
+            path: debug::get_task_list_path(),
+            delay: AUTOSAVE_DELAY,
+            backups: AUTOSAVE_BACKUPS,
+            last_change: None,
+        }
+    }
+}
+
+impl AutoSave {
+    /// Records any changes to the task list and saves it once it has been unchanged for the delay.
+    pub(crate) fn update(&mut self, task_list: &mut TaskList) -> Result<()> {
+        if task_list.take_modified() {
+            self.last_change = Some(Instant::now());
+        }
+
+        match self.last_change {
+            Some(last_change) if last_change.elapsed() >= self.delay => self.flush(task_list),
+            _ => Ok(()),
+        }
+    }
+
+    /// Gets how long until the next save is due, or None if there are no unsaved changes.
+    pub(crate) fn time_until_save(&self) -> Option<Duration> {
+        self.last_change
+            .map(|last_change| self.delay.saturating_sub(last_change.elapsed()))
+    }
+
+    /// Saves the task list immediately if there are any unsaved changes. If the save fails, the
+    /// changes are kept unsaved and the save is tried again after the delay.
+    pub(crate) fn flush(&mut self, task_list: &mut TaskList) -> Result<()> {
+        if task_list.take_modified() || self.last_change.is_some() {
+            if let Err(error) = task_list.save(&self.path, self.backups) {
+                self.last_change = Some(Instant::now());
+                return Err(error);
+            }
+            self.last_change = None;
+        }
+        Ok(())
+    }
+}
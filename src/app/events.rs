@@ -1,9 +1,33 @@
 ﻿use crate::app::{App, AppMode, KeyEventHandler};
+use crate::task::Task;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 use std::io;
+use std::time::Duration;
+
+/// How often the screen is redrawn while a task is being tracked, so that its timer stays current.
+const TICK_RATE: Duration = Duration::from_secs(1);
 
 impl App {
     pub(crate) fn handle_events(&mut self) -> io::Result<()> {
+        // Only wait for input until the next tick or autosave, so that timers are redrawn and the
+        // autosave is written on time. With neither due, wait for input without redrawing.
+        let tick = self
+            .task_list_controller
+            .task_list
+            .tasks
+            .iter()
+            .any(Task::is_tracking)
+            .then_some(TICK_RATE);
+        let timeout = match (tick, self.autosave.time_until_save()) {
+            (Some(tick), Some(save)) => Some(tick.min(save)),
+            (tick, save) => tick.or(save),
+        };
+        if let Some(timeout) = timeout {
+            if !event::poll(timeout)? {
+                return Ok(());
+            }
+        }
+
         match event::read()? {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 self.handle_key_event(key_event);
@@ -14,14 +38,40 @@ impl App {
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        // Only show a notice until the next key is pressed
+        self.task_list_controller.task_list.notice = None;
+
+        // Send every key to the task list while text is being entered
+        if self.task_list_controller.is_capturing_input() {
+            self.task_list_controller.handle_key_event(key_event);
+            return;
+        }
+
         match key_event.code {
             // Global key commands
             KeyCode::Char('q') => self.exit(),
             // Toggle help overlay
             KeyCode::Char('?') => self.help_visible = !self.help_visible,
+            // Toggle task detail pane
+            KeyCode::Char('p') => self.task_detail_visible = !self.task_detail_visible,
+            // Switch between the tree and Today views
+            KeyCode::Char('w') => {
+                self.mode = match self.mode {
+                    AppMode::TaskList => AppMode::TodayTasks,
+                    AppMode::TodayTasks | AppMode::Report => AppMode::TaskList,
+                }
+            }
+            // Show or hide the report of tasks that overran their estimates
+            KeyCode::Char('R') => {
+                self.mode = match self.mode {
+                    AppMode::Report => AppMode::TaskList,
+                    _ => AppMode::Report,
+                }
+            }
             _ => match self.mode {
                 AppMode::TaskList => self.task_list_controller.handle_key_event(key_event),
-                AppMode::TodayTasks => {}
+                AppMode::TodayTasks => self.handle_today_key_event(key_event),
+                AppMode::Report => self.handle_report_key_event(key_event),
             },
         }
     }
@@ -30,3 +80,38 @@ impl App {
         self.exit = true;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::app::App;
+    use crate::task_list_controller::TaskListController;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn global_keys_are_typed_while_editing() {
+        let mut app = App {
+            task_list_controller: TaskListController::new(
+                crate::task_list::tests::setup_task_list(),
+            ),
+            ..App::default()
+        };
+        for code in [
+            KeyCode::Char('a'),
+            KeyCode::Char('q'),
+            KeyCode::Char('?'),
+            KeyCode::Enter,
+        ] {
+            app.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+        assert!(!app.exit);
+        assert!(!app.help_visible);
+        assert_eq!(
+            app.task_list_controller
+                .task_list
+                .get_task(0)
+                .unwrap()
+                .title,
+            "Task 1q?"
+        );
+    }
+}
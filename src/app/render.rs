@@ -1,4 +1,4 @@
-﻿use crate::app::App;
+﻿use crate::app::{App, AppMode};
 use crate::ui::help;
 use ratatui::{
     buffer::Buffer,
@@ -11,22 +11,46 @@ use ratatui::{
 };
 
 impl App {
-    pub(crate) fn draw(&self, frame: &mut Frame) {
+    pub(crate) fn draw(&mut self, frame: &mut Frame) {
         frame.render_widget(self, frame.area());
     }
 }
 
-impl Widget for &App {
+impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let title = Line::from(" Subtask ".bold());
-        let block = Block::bordered()
+        let title = match self.mode {
+            AppMode::TaskList => Line::from(" Subtask ".bold()),
+            AppMode::TodayTasks => Line::from(" Subtask - Today ".bold()),
+            AppMode::Report => Line::from(" Subtask - Estimate overruns ".bold()),
+        };
+        let mut block = Block::bordered()
             .title(title)
             .title_bottom(footing_prompts().right_aligned())
             .border_set(border::ROUNDED);
+        if let Some(error) = &self.save_error {
+            block = block.title_bottom(Line::from(format!(" Not saved: {} ", error).red().bold()));
+        }
+        if let Some(notice) = &self.task_list_controller.task_list.notice {
+            block = block.title_bottom(Line::from(format!(" {} ", notice).yellow().bold()));
+        }
         let inner_area = block.inner(area);
         block.render(area, buf);
 
-        self.task_list_controller.render(inner_area, buf);
+        let detail_visible =
+            self.task_detail_visible || self.task_list_controller.is_detail_focused();
+        match self.mode {
+            AppMode::TodayTasks => self.render_today(inner_area, buf),
+            AppMode::Report => self.render_report(inner_area, buf),
+            // Show the task detail pane to the right of the list when it is visible or has focus
+            AppMode::TaskList if detail_visible => {
+                let [list_area, detail_area] =
+                    Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                        .areas(inner_area);
+                self.task_list_controller.render(list_area, buf);
+                self.task_list_controller.render_detail(detail_area, buf);
+            }
+            AppMode::TaskList => self.task_list_controller.render(inner_area, buf),
+        }
 
         // Render help overlay
         if self.help_visible {
@@ -43,6 +67,8 @@ impl Widget for &App {
 
 fn footing_prompts() -> Line<'static> {
     let instructions = Line::from(vec![
+        " w ".into(),
+        "<Today> ".blue().bold(),
         " ? ".into(),
         "<Help> ".green().bold(),
         " q ".into(),
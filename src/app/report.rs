@@ -0,0 +1,78 @@
+use crate::app::{App, AppMode};
+use crate::ui::format::format_duration;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Row, StatefulWidget, Table, TableState},
+};
+
+/// The state of the report of tasks that overran their estimates.
+#[derive(Debug, Default)]
+pub(crate) struct ReportState {
+    table: TableState,
+}
+
+impl App {
+    /// Handles a key press in the overrun report.
+    pub(crate) fn handle_report_key_event(&mut self, key_event: KeyEvent) {
+        let overruns = self.task_list_controller.task_list.overruns();
+        let table = &mut self.report.table;
+
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => table.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => table.select_previous(),
+            // Show the task in the tree view
+            KeyCode::Enter => {
+                let selected = table.selected().unwrap_or(0);
+                if let Some(&(pos, _)) = overruns.get(selected) {
+                    self.task_list_controller.select_pos(pos);
+                    self.mode = AppMode::TaskList;
+                }
+            }
+            KeyCode::Esc => self.mode = AppMode::TaskList,
+            _ => {}
+        }
+    }
+
+    /// Renders the tasks that took longer than their estimate, from the largest overrun down.
+    pub(crate) fn render_report(&mut self, area: Rect, buf: &mut Buffer) {
+        let task_list = &self.task_list_controller.task_list;
+        let overruns = task_list.overruns();
+        if overruns.is_empty() {
+            let line = Line::from("No tasks have taken longer than their estimate").dark_gray();
+            buf.set_line(area.x, area.y, &line, area.width);
+            return;
+        }
+
+        let rows = overruns.iter().filter_map(|&(pos, ratio)| {
+            let task = task_list.get_task(pos).ok()?;
+            Some(Row::new(vec![
+                task.title.clone(),
+                format_duration(task.expected_duration.unwrap_or(0)),
+                format_duration(task.active_time().num_minutes() as i32),
+                format!("{:.1}x", ratio),
+            ]))
+        });
+        let header = Row::new(vec!["Task", "Estimate", "Actual", "Ratio"]).bold();
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(6),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(Style::default().reversed());
+
+        let state = &mut self.report.table;
+        if state.selected().is_none() {
+            state.select_first();
+        }
+        StatefulWidget::render(table, area, buf, state);
+    }
+}
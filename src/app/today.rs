@@ -0,0 +1,193 @@
+use crate::app::{App, AppMode};
+use crate::task::TaskStatus;
+use crate::ui::format::{format_elapsed, format_relative_date};
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+};
+use std::cmp::Ordering;
+
+/// The state of the Today view.
+#[derive(Debug, Default)]
+pub(crate) struct TodayState {
+    /// The index of the selected task within the tasks for today.
+    selected: usize,
+    /// The number of rows that the view is scrolled down by.
+    scroll_offset: usize,
+}
+
+impl App {
+    /// Handles a key press in the Today view.
+    pub(crate) fn handle_today_key_event(&mut self, key_event: KeyEvent) {
+        let tasks = self.task_list_controller.task_list.today_tasks(today());
+        let Some(&pos) = tasks.get(self.today.selected) else {
+            self.today.selected = tasks.len().saturating_sub(1);
+            return;
+        };
+        let task_list = &mut self.task_list_controller.task_list;
+
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.today.selected = (self.today.selected + 1).min(tasks.len() - 1);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.today.selected = self.today.selected.saturating_sub(1);
+            }
+            KeyCode::Char(' ') => task_list.toggle_task_status(pos),
+            KeyCode::Char('s') => {
+                task_list.start_task(pos);
+            }
+            KeyCode::Char('z') => {
+                task_list.pause_task(pos);
+            }
+            KeyCode::Char('c') => {
+                task_list.complete_task(pos);
+            }
+            KeyCode::Char('u') => {
+                task_list.undo();
+            }
+            // Show the task in the tree view
+            KeyCode::Enter => {
+                self.task_list_controller.select_pos(pos);
+                self.mode = AppMode::TaskList;
+            }
+            _ => {}
+        }
+    }
+
+    /// Renders the tasks for today, grouped under the root task that they belong to.
+    pub(crate) fn render_today(&mut self, area: Rect, buf: &mut Buffer) {
+        let task_list = &self.task_list_controller.task_list;
+        let today = today();
+        let tasks = task_list.today_tasks(today);
+        if tasks.is_empty() {
+            buf.set_line(
+                area.x,
+                area.y,
+                &"Nothing to do today".dark_gray().into(),
+                area.width,
+            );
+            return;
+        }
+        self.today.selected = self.today.selected.min(tasks.len() - 1);
+
+        let mut lines = Vec::new();
+        let mut selected_row = 0;
+        let mut current_root = None;
+        for (index, &pos) in tasks.iter().enumerate() {
+            let Ok(task) = task_list.get_task(pos) else {
+                continue;
+            };
+
+            // Start a new group whenever the root task changes. A root task that is listed itself
+            // is shown as the heading of its group rather than under a copy of its title.
+            let root = task_list.get_root(pos);
+            if current_root != Some(root) {
+                if current_root.is_some() {
+                    lines.push(Line::from(""));
+                }
+                if root != pos {
+                    let title = task_list
+                        .get_task(root)
+                        .map_or("", |root| root.title.as_str());
+                    lines.push(Line::from(title.to_string()).bold());
+                }
+                current_root = Some(root);
+            }
+
+            let symbol = match task.task_status {
+                TaskStatus::Complete(_) => "●",
+                _ => "○",
+            };
+            let mut line = match root == pos {
+                true => Line::from(format!("{} {}", symbol, task.title)).bold(),
+                false => Line::from(format!("  {} {}", symbol, task.title)),
+            };
+            if let Some(due_date) = task.due_date {
+                let due = due_date.date_naive();
+                let span = Span::from(format!("  due {}", format_relative_date(due, today)));
+                match due.cmp(&today) {
+                    Ordering::Less => line.push_span(span.red()),
+                    Ordering::Equal => line.push_span(span.yellow()),
+                    Ordering::Greater => line.push_span(span.dark_gray()),
+                }
+            }
+            if task.is_tracking() {
+                line.push_span(format!("  ▶ {}", format_elapsed(task.active_time())));
+            }
+            line = match task.task_status {
+                TaskStatus::NotStarted => line,
+                TaskStatus::InProgress(_) => line.yellow().bold(),
+                TaskStatus::Complete(_) => line.green().italic(),
+            };
+            if index == self.today.selected {
+                line = line.reversed();
+                selected_row = lines.len();
+            }
+            lines.push(line);
+        }
+
+        // Scroll so that the selected task stays in view
+        let height = area.height as usize;
+        let state = &mut self.today;
+        if selected_row < state.scroll_offset {
+            state.scroll_offset = selected_row;
+        }
+        if height > 0 && selected_row >= state.scroll_offset + height {
+            state.scroll_offset = selected_row + 1 - height;
+        }
+
+        for (row, line) in lines
+            .iter()
+            .skip(state.scroll_offset)
+            .take(height)
+            .enumerate()
+        {
+            buf.set_line(area.x, area.y + row as u16, line, area.width);
+        }
+    }
+}
+
+/// Gets the current local date.
+fn today() -> chrono::NaiveDate {
+    Local::now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::App;
+    use crate::task_list_controller::TaskListController;
+    use ratatui::{buffer::Buffer, layout::Rect};
+
+    #[test]
+    fn listed_root_tasks_are_shown_once() {
+        let mut app = App {
+            task_list_controller: TaskListController::new(
+                crate::task_list::tests::setup_task_list(),
+            ),
+            ..App::default()
+        };
+        let area = Rect::new(0, 0, 30, 10);
+        let mut buf = Buffer::empty(area);
+        app.render_today(area, &mut buf);
+
+        let rows: Vec<String> = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect();
+        // Task 3 is actionable and is its own root, so it heads its group without a copy
+        assert_eq!(rows.iter().filter(|row| row.contains("Task 3")).count(), 1);
+        assert!(rows.contains(&"○ Task 3".to_string()));
+        assert!(rows.contains(&"Task 2".to_string()));
+        assert!(rows.contains(&"  ○ Task 2.1".to_string()));
+    }
+}
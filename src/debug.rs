@@ -1,5 +1,7 @@
 ﻿use std::path::PathBuf;
 
+use crate::task_list::roll_up::RollUp;
+use crate::task_list::schedule::WorkCalendar;
 use color_eyre::eyre::Result;
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
@@ -14,6 +16,20 @@ lazy_static! {
             .map(PathBuf::from);
     pub static ref LOG_ENV: String = format!("{}_LOGLEVEL", PROJECT_NAME.clone());
     pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+    pub static ref TASK_LIST_FILE: String = format!("{}.json", env!("CARGO_PKG_NAME"));
+    pub static ref SCROLL_OFF: usize = std::env::var(format!("{}_SCROLLOFF", PROJECT_NAME.clone()))
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(3);
+    pub static ref ROLL_UP: RollUp = std::env::var(format!("{}_ROLLUP", PROJECT_NAME.clone()))
+        .ok()
+        .and_then(|value| RollUp::parse(&value))
+        .unwrap_or_default();
+    pub static ref CALENDAR: WorkCalendar =
+        std::env::var(format!("{}_CALENDAR", PROJECT_NAME.clone()))
+            .ok()
+            .and_then(|value| WorkCalendar::parse(&value))
+            .unwrap_or_default();
 }
 
 fn project_directory() -> Option<ProjectDirs> {
@@ -31,6 +47,11 @@ pub fn get_data_dir() -> PathBuf {
     directory
 }
 
+/// Gets the path of the file that the task list is saved to.
+pub fn get_task_list_path() -> PathBuf {
+    get_data_dir().join(TASK_LIST_FILE.clone())
+}
+
 pub fn initialize_logging() -> Result<()> {
     let directory = get_data_dir();
     std::fs::create_dir_all(directory.clone())?;
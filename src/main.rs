@@ -1,5 +1,6 @@
 use crate::app::App;
-use std::io;
+use color_eyre::eyre::{eyre, Result};
+use std::path::Path;
 
 mod app;
 mod debug;
@@ -8,9 +9,31 @@ mod task_list;
 mod task_list_controller;
 mod ui;
 
-fn main() -> io::Result<()> {
+fn main() -> Result<()> {
+    let mut app = App::load()?;
+
+    // Import or export the task list instead of running the interface when asked to
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => {}
+        ["import", path] => {
+            app.import(Path::new(path))?;
+            return app.save();
+        }
+        ["export", path] => return app.export(Path::new(path)),
+        _ => return Err(eyre!("Usage: subtask [import <file> | export <file>]")),
+    }
+
     let mut terminal = ratatui::init();
-    let app_result = App::default().run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     ratatui::restore();
-    app_result
+    // Always try to save the task list, even if the interface stopped with an error
+    let save_result = app.save();
+    app_result?;
+    save_result
 }
@@ -1,24 +1,34 @@
-﻿use chrono::{DateTime, Local};
+﻿use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
 use std::cmp::PartialEq;
 use uuid::Uuid;
 
 pub mod render;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TaskStatus {
     NotStarted,
     InProgress(DateTime<Local>),
     Complete(DateTime<Local>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ExecutionOrder {
     Series,
     Parallel,
 }
 
+/// A period of time spent working on a task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// When work on the task started.
+    pub start: DateTime<Local>,
+    /// When work on the task stopped, or None if it is still being worked on.
+    pub end: Option<DateTime<Local>>,
+}
+
 /// A task to be executed.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     /// The unique identifier of the task.
     pub id: Uuid,
@@ -44,6 +54,16 @@ pub struct Task {
     pub due_date: Option<DateTime<Local>>,
 
     pub expected_duration: Option<i32>,
+    /// True if the subtasks of the task are hidden.
+    #[serde(default)]
+    pub folded: bool,
+    /// The periods of time spent working on the task, the last of which may still be running.
+    #[serde(default)]
+    pub sessions: Vec<Session>,
+    /// Properties of the task from an imported file that are not otherwise used, kept so that
+    /// they can be written back out when the task is exported.
+    #[serde(default)]
+    pub properties: Vec<(String, String)>,
 }
 
 impl Task {
@@ -61,21 +81,19 @@ impl Task {
             task_status: TaskStatus::NotStarted,
             execution_order: ExecutionOrder::Series,
             expected_duration: None,
+            folded: false,
+            sessions: vec![],
+            properties: vec![],
             depth,
         }
     }
 
-    pub(crate) fn toggle_status(&mut self) {
+    /// Gets the status that the task moves to when its status is toggled.
+    pub(crate) fn next_status(&self) -> TaskStatus {
         match self.task_status {
-            TaskStatus::NotStarted => {
-                self.task_status = TaskStatus::InProgress(Local::now());
-            }
-            TaskStatus::InProgress(_) => {
-                self.task_status = TaskStatus::Complete(Local::now());
-            }
-            TaskStatus::Complete(_) => {
-                self.task_status = TaskStatus::NotStarted;
-            }
+            TaskStatus::NotStarted => TaskStatus::InProgress(Local::now()),
+            TaskStatus::InProgress(_) => TaskStatus::Complete(Local::now()),
+            TaskStatus::Complete(_) => TaskStatus::NotStarted,
         }
     }
 
@@ -84,34 +102,55 @@ impl Task {
         self.execution_order = order;
     }
 
-    /// Sets the task to an active status and starts logging the time since it was active.
-    fn set_active(&mut self, new_value: bool) {
-        if new_value && !self.is_next {
-            self.task_status = TaskStatus::InProgress(Local::now())
-        }
+    /// Checks whether time is currently being tracked against the task.
+    pub(crate) fn is_tracking(&self) -> bool {
+        self.sessions.last().is_some_and(|session| session.end.is_none())
     }
 
-    /// Calculates the amount of time that has elapsed between when the task was made active and now.
-    fn active_time(&self) -> i64 {
-        match self.task_status {
-            TaskStatus::InProgress(start_time) => {
-                let now = Local::now();
-                now.signed_duration_since(start_time).num_minutes()
-            }
-            _ => 0,
+    /// Starts a new session of work on the task, unless one is already running.
+    pub(crate) fn start_session(&mut self) {
+        if !self.is_tracking() {
+            self.sessions.push(Session {
+                start: Local::now(),
+                end: None,
+            });
         }
     }
 
-    /// Calculates the amount of time that was has elapsed between when the task was first made active to when it was completed.
-    fn completion_time(&self) -> Option<i64> {
-        match self.task_status {
-            TaskStatus::Complete(end_time) => {
-                let now = Local::now();
-                Option::from(now.signed_duration_since(end_time).num_minutes())
-            }
-            _ => None,
+    /// Stops the running session of work on the task, if there is one.
+    pub(crate) fn stop_session(&mut self) {
+        if let Some(session) = self.sessions.last_mut() {
+            session.end.get_or_insert_with(Local::now);
         }
     }
+
+    /// Calculates the total time spent working on the task, including any running session.
+    pub(crate) fn active_time(&self) -> TimeDelta {
+        let now = Local::now();
+        self.sessions
+            .iter()
+            .map(|session| session.end.unwrap_or(now) - session.start)
+            .sum()
+    }
+
+    /// Calculates the time that elapsed between when work on the task first started and when it
+    /// was completed, or None if the task is not complete.
+    pub(crate) fn completion_time(&self) -> Option<TimeDelta> {
+        let TaskStatus::Complete(end_time) = self.task_status else {
+            return None;
+        };
+        let start_time = self
+            .sessions
+            .first()
+            .map_or(self.creation_date, |session| session.start);
+        Some(end_time - start_time)
+    }
+
+    /// Checks whether every property of the task matches another task, whereas `==` only compares
+    /// their ids.
+    pub(crate) fn is_identical(&self, other: &Task) -> bool {
+        serde_json::to_value(self).ok() == serde_json::to_value(other).ok()
+    }
 }
 
 impl PartialEq for Task {
@@ -1,11 +1,17 @@
 ﻿use crate::task::{ExecutionOrder, Task, TaskStatus};
+use crate::ui::format::{format_elapsed, format_relative_date};
+use chrono::Local;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     prelude::{Line, StatefulWidget},
     style::Stylize,
+    text::Span,
 };
 
+/// The number of days before its due date that a task is highlighted as due soon.
+const DUE_SOON_DAYS: i64 = 2;
+
 impl StatefulWidget for &Task {
     type State = TaskState;
 
@@ -18,10 +24,44 @@ impl StatefulWidget for &Task {
 
         // Add an extra space to the left of the task title if it is a branch task to allow room for the joiner.
         let mut line = match state.leaf {
-            true => Line::from(format!("{} {}\r\n", symbol, self.title)),
-            false => Line::from(format!("{}  {}\r\n", symbol, self.title)),
+            true => Line::from(format!("{} {}", symbol, self.title)),
+            false => Line::from(format!("{}  {}", symbol, self.title)),
         };
 
+        // Show how much of the work below a branch task is complete
+        if let Some(percent) = state.percent_complete {
+            line.push_span(format!(" {}%", percent));
+        }
+
+        // Show the time spent on a task while its timer is running
+        if state.tracking {
+            line.push_span(format!(" ▶ {}", format_elapsed(self.active_time())));
+        }
+
+        // Show when an unfinished task is due, highlighting tasks that are overdue or due soon
+        let complete = matches!(self.task_status, TaskStatus::Complete(_));
+        if let Some(due_date) = self.due_date.filter(|_| !complete) {
+            let today = Local::now().date_naive();
+            let due = due_date.date_naive();
+            let label = format!(" due {}", format_relative_date(due, today));
+            let span = match (due - today).num_days() {
+                days if days < 0 => Span::from(label).red().bold(),
+                days if days <= DUE_SOON_DAYS => Span::from(label).yellow(),
+                _ => Span::from(label).dark_gray(),
+            };
+            line.push_span(span);
+        }
+
+        // Warn when a task is due after one of its parents
+        if state.due_conflict {
+            line.push_span(Span::from(" ⚠").yellow());
+        }
+
+        // Show how many subtasks are hidden by a folded task
+        if state.hidden_subtasks > 0 {
+            line.push_span(format!(" [+{}]", state.hidden_subtasks));
+        }
+
         if state.selected {
             line = line.underlined();
         }
@@ -30,6 +70,11 @@ impl StatefulWidget for &Task {
             line = line.dark_gray();
         }
 
+        // Highlight the tasks that determine the total duration of their root task
+        if self.is_critical {
+            line = line.red();
+        }
+
         match self.task_status {
             TaskStatus::NotStarted => {}
             TaskStatus::InProgress(_) => {
@@ -53,15 +98,35 @@ pub struct TaskState {
     pub leaf: bool,
     /// True if the task is a candidate to be completed next
     pub next: bool,
+    /// The number of subtasks hidden because the task is folded
+    pub hidden_subtasks: usize,
+    /// The percentage of the subtasks that are complete, if the task has subtasks
+    pub percent_complete: Option<u8>,
+    /// True if time is being tracked against the task
+    pub tracking: bool,
+    /// True if the task is due after one of its parents
+    pub due_conflict: bool,
 }
 
 impl TaskState {
+    /// Gets the horizontal offset of the task title from the start of the task.
+    pub fn title_offset(&self) -> u16 {
+        match self.leaf {
+            true => 2,
+            false => 3,
+        }
+    }
+
     pub fn default() -> TaskState {
         TaskState {
             selected: false,
             visible: true,
             leaf: false,
             next: false,
+            hidden_subtasks: 0,
+            percent_complete: None,
+            tracking: false,
+            due_conflict: false,
         }
     }
 }
@@ -1,8 +1,26 @@
 ﻿mod actions;
+mod dates;
 mod error;
+mod estimates;
+mod folding;
+mod formats;
+mod graph;
+mod history;
+mod ical;
 pub mod index;
-
-use crate::task::{ExecutionOrder, Task};
+mod markdown;
+mod org;
+pub mod roll_up;
+pub mod schedule;
+mod storage;
+mod today;
+mod todo_txt;
+
+use crate::task::Task;
+use crate::task_list::history::History;
+use crate::task_list::roll_up::RollUp;
+use crate::task_list::schedule::WorkCalendar;
+use chrono::TimeDelta;
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -31,6 +49,18 @@ pub struct TaskList {
     pub(crate) depth_index: HashMap<i8, Vec<usize>>,
     /// An index of all the tasks that are up next
     pub(crate) next_tasks: Vec<usize>,
+    /// An index of all the tasks that are not hidden inside a folded task
+    pub(crate) visible_tasks: Vec<usize>,
+    /// The rules for rolling up the status of subtasks to their parents
+    pub(crate) roll_up: RollUp,
+    /// The working hours that the tasks are scheduled into
+    pub(crate) calendar: WorkCalendar,
+    /// True if the list has been changed since it was last saved
+    modified: bool,
+    /// The changes that can be undone and redone
+    history: History,
+    /// A message for the user about why the last change was not made in full
+    pub(crate) notice: Option<String>,
 }
 
 impl TaskList {
@@ -42,9 +72,25 @@ impl TaskList {
             title_index: HashMap::new(),
             depth_index: HashMap::new(),
             next_tasks: vec![],
+            visible_tasks: vec![],
+            roll_up: RollUp::default(),
+            calendar: WorkCalendar::default(),
+            modified: false,
+            history: History::default(),
+            notice: None,
         }
     }
 
+    /// Marks the list as changed so that it will be picked up by the next save.
+    pub(crate) fn mark_modified(&mut self) {
+        self.modified = true;
+    }
+
+    /// Returns true if the list has been changed since the last call, and resets the flag.
+    pub(crate) fn take_modified(&mut self) -> bool {
+        std::mem::take(&mut self.modified)
+    }
+
     /// Gets the number of tasks
     pub fn len(&self) -> usize {
         self.tasks.len()
@@ -117,7 +163,7 @@ impl TaskList {
 
     /// Get all the start and end indices of the range of subtasks for the selected subtask.
     /// Returns the same index if the task has no subtasks.
-    fn get_last_subtask_pos(&self, pos: usize) -> usize {
+    pub(crate) fn get_last_subtask_pos(&self, pos: usize) -> usize {
         // Check that the task is not at the end of the list, and that the task below it is not at the same depth or less.
         if !self.has_subtasks(pos) {
             return pos;
@@ -187,38 +233,48 @@ impl TaskList {
         };
 
         let depth_tasks = self.depth_index.get(&(depth - 1))?;
-        let partition_point = depth_tasks.partition_point(|&p| p < pos);
 
         // Partition the tasks in the depth index above the current, then use this to find the nearest parent.
-        Some(depth_tasks[depth_tasks.partition_point(|p| p < &pos) - 1])
+        let partition_point = depth_tasks.partition_point(|&p| p < pos);
+        Some(depth_tasks[partition_point.checked_sub(1)?])
     }
 
-    /// Calculate the total duration of the task and its subtasks depending on their execution order.
-    /// Returns the total duration in minutes.
-    fn calculate_task_duration(&self, pos: usize) -> i32 {
-        let Ok(task) = self.get_task(pos) else {
-            return 0;
-        };
-
-        let subtasks = self.get_direct_subtasks(pos);
-        if subtasks.is_empty() {
-            return task.expected_duration.unwrap_or(0);
+    /// Gets the root task that a task is within, which is the task itself if it is a root task.
+    pub(crate) fn get_root(&self, pos: usize) -> usize {
+        let mut root = pos;
+        while let Some(parent) = self.get_parent(root) {
+            root = parent;
         }
+        root
+    }
 
-        // Calculate the task duration recursively based on the execution order.
-        match task.execution_order {
-            // If the execution order is in series, the total duration is just the sum of all the subtask durations.
-            ExecutionOrder::Series => subtasks
-                .iter()
-                .map(|&subtask| self.calculate_task_duration(subtask))
-                .sum::<i32>(),
-            // If the execution order is in parallel, the total duration is the maximum duration of the subtasks.
-            ExecutionOrder::Parallel => subtasks
-                .iter()
-                .map(|&subtask| self.calculate_task_duration(subtask))
-                .max()
-                .unwrap_or(0),
+    /// Gets the next task below at the same depth with the same parent, skipping over the subtasks
+    /// of the task at the specified position.
+    pub(crate) fn get_next_sibling(&self, pos: usize) -> Option<usize> {
+        let depth = self.get_task(pos).ok()?.depth;
+        let next_pos = self.get_last_subtask_pos(pos) + 1;
+        let next = self.tasks.get(next_pos)?;
+        (next.depth == depth).then_some(next_pos)
+    }
+
+    /// Gets the previous task above at the same depth with the same parent, skipping over its
+    /// subtasks.
+    pub(crate) fn get_previous_sibling(&self, pos: usize) -> Option<usize> {
+        let depth = self.get_task(pos).ok()?.depth;
+        // The nearest task above at the same depth or less is either the sibling or the parent
+        let previous_pos = (0..pos).rev().find(|&p| self.tasks[p].depth <= depth)?;
+        (self.tasks[previous_pos].depth == depth).then_some(previous_pos)
+    }
+
+    /// Calculates the total time spent working on a task and all of its subtasks.
+    pub(crate) fn actual_time(&self, pos: usize) -> TimeDelta {
+        if pos >= self.len() {
+            return TimeDelta::zero();
         }
+        self.tasks[pos..=self.get_last_subtask_pos(pos)]
+            .iter()
+            .map(Task::active_time)
+            .sum()
     }
 
     /// Prints a simple debugging string representation of the task list.
@@ -235,7 +291,7 @@ impl TaskList {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
     pub(crate) fn setup_task_list() -> TaskList {
@@ -320,6 +376,35 @@ mod tests {
         assert_eq!(task_list.get_parent(8), None);
     }
 
+    #[test]
+    fn get_siblings_skip_subtasks() {
+        let task_list = setup_task_list();
+        assert_eq!(task_list.get_next_sibling(0), Some(4));
+        assert_eq!(task_list.get_next_sibling(3), None);
+        assert_eq!(task_list.get_next_sibling(8), None);
+        assert_eq!(task_list.get_previous_sibling(8), Some(4));
+        assert_eq!(task_list.get_previous_sibling(6), Some(5));
+        assert_eq!(task_list.get_previous_sibling(5), None);
+        assert_eq!(task_list.get_previous_sibling(0), None);
+    }
+
+    #[test]
+    fn critical_path_follows_longest_parallel_subtask() {
+        let mut task_list = setup_task_list();
+        for (pos, duration) in [(1, 10), (2, 30), (3, 20), (5, 10), (6, 10)] {
+            task_list.get_mut_task(pos).unwrap().expected_duration = Some(duration);
+        }
+        task_list.toggle_execution_order(0);
+
+        let critical: Vec<usize> = (0..task_list.len())
+            .filter(|&pos| task_list.get_task(pos).unwrap().is_critical)
+            .collect();
+        // Only the longest parallel subtask of Task 1 is critical, every series subtask of Task 2
+        // with a duration is critical, and Task 3 has no duration so it is not critical at all.
+        assert_eq!(critical, vec![0, 2, 4, 5, 6]);
+        assert_eq!(task_list.calculate_task_duration(0), 30);
+    }
+
     #[test]
     fn get_next_subtasks_series() {
         let task_list = setup_task_list();
@@ -334,4 +419,27 @@ mod tests {
         task_list.toggle_execution_order(4);
         assert_eq!(task_list.next_tasks, vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
     }
+
+    #[test]
+    fn get_next_subtasks_skips_complete_tasks() {
+        let mut task_list = setup_task_list();
+        // Keep parents open when their subtasks are complete
+        task_list.roll_up = RollUp::parse("none").unwrap();
+
+        // Completing the first subtask of a series advances to the next one
+        task_list.toggle_task_status(1);
+        task_list.toggle_task_status(1);
+        assert_eq!(task_list.next_tasks, vec![0, 2, 4, 5, 8]);
+
+        // A task whose subtasks are all complete is actionable itself
+        task_list.complete_task(2).complete_task(3);
+        assert_eq!(task_list.next_tasks, vec![0, 4, 5, 8]);
+        assert!(task_list.is_actionable(0));
+
+        // Complete tasks are skipped along with all of their subtasks
+        task_list.complete_task(0);
+        task_list.toggle_execution_order(4);
+        task_list.complete_task(6);
+        assert_eq!(task_list.next_tasks, vec![4, 5, 7, 8]);
+    }
 }
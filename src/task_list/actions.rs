@@ -1,25 +1,30 @@
 ﻿use crate::task::ExecutionOrder::{Parallel, Series};
-use crate::task::Task;
-use crate::task_list::{Direction, TaskList, TaskListError};
+use crate::task::{Task, TaskStatus};
+use crate::task_list::history::Change;
+use crate::task_list::{Direction, TaskList};
+use chrono::Local;
 
 impl TaskList {
     /// Adds a new root task to the end of the list
     pub fn add_new_root_task_at_end(&mut self, title: &str) -> &mut Self {
         let task = Task::new(title, 0);
-        self.tasks.push(task);
-        self.rebuild_all_indices();
+        self.execute(Change::Insert {
+            pos: self.len(),
+            tasks: vec![task],
+        });
         self
     }
 
-    /// Add a new task at a specific index
-    pub fn add_new_task(&mut self, title: &str, pos: usize) -> &mut Self {
-        // Look at the index above and match the depth
-        let depth = self.neighbour_depth(pos, &Direction::Up);
+    /// Add a new task at a specific index and depth. An index past the end of the list adds the
+    /// task to the end.
+    pub fn add_new_task(&mut self, title: &str, pos: usize, depth: i8) -> &mut Self {
         let task = Task::new(title, depth);
-        let task_pos = self.get_pos(pos, &Direction::None);
+        let task_pos = pos.min(self.len());
 
-        self.tasks.insert(task_pos, task);
-        self.rebuild_all_indices();
+        self.execute(Change::Insert {
+            pos: task_pos,
+            tasks: vec![task],
+        });
         self
     }
 
@@ -30,8 +35,10 @@ impl TaskList {
         let task = Task::new(title, depth + 1);
         let task_pos = self.get_pos(pos, &Direction::None);
 
-        self.tasks.insert(task_pos + 1, task);
-        self.rebuild_all_indices();
+        self.execute(Change::Insert {
+            pos: task_pos + 1,
+            tasks: vec![task],
+        });
         self
     }
 
@@ -43,36 +50,126 @@ impl TaskList {
         }
         // Get the position of the last subtask, then remove all the tasks between the current task and the last subtask.
         let end = self.get_last_subtask_pos(pos);
-        for i in (pos..=end).rev() {
-            self.tasks.remove(i);
-        }
+        let tasks = self.tasks[pos..=end].to_vec();
 
-        self.rebuild_all_indices();
+        self.execute(Change::Remove { pos, tasks });
         self
     }
 
+    /// Moves the task at a position on to its next status, stopping the time tracked against it
+    /// unless it is moving into progress.
     pub fn toggle_task_status(&mut self, pos: usize) {
-        let Ok(task) = self.get_mut_task(pos) else {
+        let Ok(task) = self.get_task(pos) else {
             return;
         };
+        let to = task.next_status();
+        let stop = !matches!(to, TaskStatus::InProgress(_));
+        self.change_task_status(
+            pos,
+            |task| {
+                if stop {
+                    task.stop_session()
+                }
+            },
+            |_| Some(to),
+        );
+    }
 
-        task.toggle_status();
+    /// Starts the task at a position and tracks the time spent on it. Tasks that are already in
+    /// progress resume tracking time, and complete tasks stay complete.
+    pub fn start_task(&mut self, pos: usize) -> &mut Self {
+        self.change_task_status(pos, Task::start_session, |status| match status {
+            TaskStatus::NotStarted => Some(TaskStatus::InProgress(Local::now())),
+            _ => None,
+        })
     }
 
-    /// Moves a subtask up or down in the list
-    pub fn move_task(&mut self, pos: usize, dir: &Direction) -> Result<&mut Self, TaskListError> {
-        let swap_pos = self.get_pos(pos, dir);
-        let task = self.get_task(pos)?;
+    /// Stops tracking the time spent on the task at a position without changing its status.
+    pub fn pause_task(&mut self, pos: usize) -> &mut Self {
+        self.change_task_status(pos, Task::stop_session, |_| None)
+    }
+
+    /// Completes the task at a position and stops tracking the time spent on it.
+    pub fn complete_task(&mut self, pos: usize) -> &mut Self {
+        self.change_task_status(pos, Task::stop_session, |status| match status {
+            TaskStatus::Complete(_) => None,
+            _ => Some(TaskStatus::Complete(Local::now())),
+        })
+    }
 
-        // A task can only be moved up/down if the item above/below it has an equal depth
-        let neighbour_depth = self.neighbour_depth(pos, dir);
-        if task.depth != neighbour_depth {
-            return Ok(self);
+    /// Changes the work sessions of the task at a position and then its status to the status
+    /// returned for its current status, leaving the status unchanged if None is returned. Both
+    /// changes are made as a single change along with any roll-up to the parents of the task.
+    fn change_task_status(
+        &mut self,
+        pos: usize,
+        sessions: impl FnOnce(&mut Task),
+        status: impl FnOnce(&TaskStatus) -> Option<TaskStatus>,
+    ) -> &mut Self {
+        let Ok(task) = self.get_task(pos) else {
+            return self;
+        };
+
+        let mut changes = vec![];
+        let mut tracked = task.clone();
+        sessions(&mut tracked);
+        if tracked.sessions != task.sessions {
+            changes.push(Change::Update {
+                pos,
+                from: Box::new(task.clone()),
+                to: Box::new(tracked),
+            });
+        }
+        if let Some(to) = status(&task.task_status) {
+            changes.push(Change::Status {
+                pos,
+                from: task.task_status.clone(),
+                to,
+            });
         }
 
-        self.tasks.swap(pos, swap_pos);
-        self.rebuild_all_indices();
-        Ok(self)
+        if let Some(change) = self.roll_up_status(changes) {
+            self.execute(change);
+        }
+        self
+    }
+
+    /// Renames the task at a specific index
+    pub fn set_task_title(&mut self, pos: usize, title: &str) -> &mut Self {
+        let Ok(task) = self.get_task(pos) else {
+            return self;
+        };
+        if task.title == title {
+            return self;
+        }
+
+        self.execute(Change::Title {
+            pos,
+            from: task.title.clone(),
+            to: title.to_string(),
+        });
+        self
+    }
+
+    /// Changes any of the fields of the task at a specific index as a single change. The depth of
+    /// the task should be changed with `promote_task` and `demote_task` instead.
+    pub fn update_task(&mut self, pos: usize, update: impl FnOnce(&mut Task)) -> &mut Self {
+        let Ok(task) = self.get_task(pos) else {
+            return self;
+        };
+
+        let mut updated = task.clone();
+        update(&mut updated);
+        // Don't record an undo step for an update that changed nothing
+        if updated.is_identical(task) {
+            return self;
+        }
+        self.execute(Change::Update {
+            pos,
+            from: Box::new(task.clone()),
+            to: Box::new(updated),
+        });
+        self
     }
 
     /// Promote a task by making all of its siblings by subtracting 1 from the task's depth.
@@ -89,7 +186,7 @@ impl TaskList {
 
     /// Change the depth of a task by a given quantity.
     fn change_task_depth(&mut self, pos: usize, depth_change: i8) -> &mut Self {
-        let Some(task) = self.tasks.get_mut(pos) else {
+        let Some(task) = self.tasks.get(pos) else {
             return self;
         };
 
@@ -99,25 +196,175 @@ impl TaskList {
         }
 
         // Move the task
-        task.depth += depth_change;
-
-        self.rebuild_all_indices();
+        self.execute(Change::Depth {
+            pos,
+            change: depth_change,
+        });
         self
     }
 
     pub(crate) fn toggle_execution_order(&mut self, pos: usize) -> &mut Self {
-        let Ok(task) = self.get_mut_task(pos) else {
+        let Ok(task) = self.get_task(pos) else {
+            return self;
+        };
+
+        let order = match task.execution_order {
+            Series => Parallel,
+            Parallel => Series,
+        };
+
+        self.execute(Change::Order {
+            pos,
+            from: task.execution_order.clone(),
+            to: order,
+        });
+        self
+    }
+
+    /// Gets the last position covered by a range of tasks, including all the subtasks of every
+    /// task in the range.
+    pub(crate) fn get_last_range_pos(&self, start: usize, end: usize) -> usize {
+        (start..=end)
+            .map(|pos| self.get_last_subtask_pos(pos))
+            .max()
+            .unwrap_or(end)
+    }
+
+    /// Delete a range of tasks along with all of their subtasks as a single change.
+    pub fn delete_tasks(&mut self, start: usize, end: usize) -> &mut Self {
+        if start > end || end >= self.len() {
+            return self;
+        }
+
+        let last = self.get_last_range_pos(start, end);
+        let tasks = self.tasks[start..=last].to_vec();
+        self.execute(Change::Remove { pos: start, tasks });
+        self
+    }
+
+    /// Toggle the status of a range of tasks as a single change. Every task in the range is given
+    /// the status that the first task toggles to, so the range ends up with a consistent status.
+    pub fn toggle_tasks_status(&mut self, start: usize, end: usize) -> &mut Self {
+        let Ok(first) = self.get_task(start) else {
             return self;
         };
+        let status = first.next_status();
 
-        task.execution_order = match task.execution_order {
+        let changes = (start..=end.min(self.len() - 1))
+            .map(|pos| Change::Status {
+                pos,
+                from: self.tasks[pos].task_status.clone(),
+                to: status.clone(),
+            })
+            .collect();
+        if let Some(change) = self.roll_up_status(changes) {
+            self.execute(change);
+        }
+        self
+    }
+
+    /// Toggle the execution order of a range of tasks as a single change. Every task in the range
+    /// is given the order that the first task toggles to.
+    pub fn toggle_tasks_execution_order(&mut self, start: usize, end: usize) -> &mut Self {
+        let Ok(first) = self.get_task(start) else {
+            return self;
+        };
+        let order = match first.execution_order {
             Series => Parallel,
             Parallel => Series,
         };
 
-        self.rebuild_next_tasks();
+        let changes = (start..=end.min(self.len() - 1))
+            .map(|pos| Change::Order {
+                pos,
+                from: self.tasks[pos].execution_order.clone(),
+                to: order.clone(),
+            })
+            .collect();
+        self.execute(Change::Batch(changes));
         self
     }
+
+    /// Promote a range of tasks and all of their subtasks as a single change.
+    pub fn promote_tasks(&mut self, start: usize, end: usize) -> &mut Self {
+        self.change_tasks_depth(start, end, -1)
+    }
+
+    /// Demote a range of tasks and all of their subtasks as a single change.
+    pub fn demote_tasks(&mut self, start: usize, end: usize) -> &mut Self {
+        self.change_tasks_depth(start, end, 1)
+    }
+
+    /// Change the depth of a range of tasks and all of their subtasks by a given quantity.
+    fn change_tasks_depth(&mut self, start: usize, end: usize, depth_change: i8) -> &mut Self {
+        if start > end || end >= self.len() {
+            return self;
+        }
+        let last = self.get_last_range_pos(start, end);
+
+        // Make sure that none of the depths go below 0
+        let min_depth = self.tasks[start..=last]
+            .iter()
+            .map(|task| task.depth)
+            .min()
+            .unwrap_or(0);
+        if depth_change.is_negative() && min_depth < -depth_change {
+            return self;
+        }
+
+        let changes = (start..=last)
+            .map(|pos| Change::Depth {
+                pos,
+                change: depth_change,
+            })
+            .collect();
+        self.execute(Change::Batch(changes));
+        self
+    }
+
+    /// Moves a range of tasks and all of their subtasks up or down past the neighbouring sibling
+    /// and its subtasks, as a single change. Returns the new start position of the range, or None
+    /// if the range could not be moved.
+    pub fn move_tasks(&mut self, start: usize, end: usize, dir: &Direction) -> Option<usize> {
+        if start > end || end >= self.len() {
+            return None;
+        }
+        let depth = self.tasks[start].depth;
+        let last = self.get_last_range_pos(start, end);
+
+        // The range can only be moved as a block if it does not contain any of its parents
+        if self.tasks[start..=last]
+            .iter()
+            .any(|task| task.depth < depth)
+        {
+            return None;
+        }
+
+        let new_start = match dir {
+            Direction::Up => self.get_previous_sibling(start)?,
+            Direction::Down => {
+                let sibling = last + 1;
+                if self.tasks.get(sibling)?.depth != depth {
+                    return None;
+                }
+                start + self.get_last_subtask_pos(sibling) - last
+            }
+            _ => return None,
+        };
+
+        let tasks = self.tasks[start..=last].to_vec();
+        self.execute(Change::Batch(vec![
+            Change::Remove {
+                pos: start,
+                tasks: tasks.clone(),
+            },
+            Change::Insert {
+                pos: new_start,
+                tasks,
+            },
+        ]));
+        Some(new_start)
+    }
 }
 
 mod test {
@@ -134,15 +381,118 @@ mod test {
         let mut task_list = crate::task_list::tests::setup_task_list();
 
         // Adds at start of list, pushing Task 1 down
-        task_list.add_new_task(&String::from("Task 0"), 0);
+        task_list.add_new_task(&String::from("Task 0"), 0, 0);
         // Adds at middle of list, becoming parent of Task 1.3
-        task_list.add_new_task(&String::from("Task 4"), 4);
+        task_list.add_new_task(&String::from("Task 4"), 4, 1);
 
         let expected = "Task 0\r\nTask 1\r\n>Task 1.1\r\n>Task 1.2\r\n>Task 4\r\n>Task 1.3\r\nTask 2\r\n>Task 2.1\r\n>Task 2.2\r\n>Task 2.3\r\nTask 3\r\n";
 
         assert_eq!(task_list.print_debug(), expected);
     }
 
+    #[test]
+    fn add_new_task_adds_to_end_and_empty_lists() {
+        let mut task_list = crate::task_list::TaskList::new("Task List");
+        task_list.add_new_task("Task 1", 0, 0);
+        task_list.add_new_task("Task 1.1", 5, 1);
+
+        assert_eq!(task_list.print_debug(), "Task 1\r\n>Task 1.1\r\n");
+    }
+
+    #[test]
+    fn set_task_title_updates_title_index() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.set_task_title(2, "Renamed");
+
+        assert_eq!(task_list.get_task(2).unwrap().title, "Renamed");
+        assert_eq!(task_list.title_index.get("Renamed"), Some(&2));
+        assert_eq!(task_list.title_index.get("Task 1.2"), None);
+
+        task_list.undo();
+        assert_eq!(task_list.title_index.get("Task 1.2"), Some(&2));
+    }
+
+    #[test]
+    fn delete_tasks_removes_range_with_subtasks() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.delete_tasks(3, 4);
+        assert_eq!(
+            task_list.print_debug(),
+            "Task 1\r\n>Task 1.1\r\n>Task 1.2\r\nTask 3\r\n"
+        );
+
+        // The whole range is restored by a single undo
+        task_list.undo();
+        assert_eq!(task_list.len(), 9);
+    }
+
+    #[test]
+    fn move_tasks_moves_subtrees_past_siblings() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+
+        assert_eq!(
+            task_list.move_tasks(4, 4, &crate::task_list::Direction::Up),
+            Some(0)
+        );
+        assert_eq!(task_list.print_debug(), "Task 2\r\n>Task 2.1\r\n>Task 2.2\r\n>Task 2.3\r\nTask 1\r\n>Task 1.1\r\n>Task 1.2\r\n>Task 1.3\r\nTask 3\r\n");
+
+        assert_eq!(
+            task_list.move_tasks(0, 0, &crate::task_list::Direction::Down),
+            Some(4)
+        );
+        assert_eq!(
+            task_list.move_tasks(1, 2, &crate::task_list::Direction::Down),
+            Some(2)
+        );
+        assert_eq!(task_list.print_debug(), "Task 1\r\n>Task 1.3\r\n>Task 1.1\r\n>Task 1.2\r\nTask 2\r\n>Task 2.1\r\n>Task 2.2\r\n>Task 2.3\r\nTask 3\r\n");
+
+        // A range cannot move past its parent
+        assert_eq!(
+            task_list.move_tasks(1, 1, &crate::task_list::Direction::Up),
+            None
+        );
+    }
+
+    #[test]
+    fn change_tasks_depth_includes_subtasks() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.demote_tasks(4, 4);
+        assert_eq!(task_list.get_direct_subtasks(0), vec![1, 2, 3, 4]);
+        assert_eq!(task_list.get_direct_subtasks(4), vec![5, 6, 7]);
+
+        // Root tasks cannot be promoted any further
+        task_list.promote_tasks(0, 8);
+        assert_eq!(task_list.get_direct_subtasks(0), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn update_task_can_be_undone() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.update_task(1, |task| {
+            task.description = String::from("Notes");
+            task.expected_duration = Some(45);
+        });
+        assert_eq!(task_list.get_task(1).unwrap().expected_duration, Some(45));
+
+        task_list.undo();
+        let task = task_list.get_task(1).unwrap();
+        assert_eq!(task.description, "");
+        assert_eq!(task.expected_duration, None);
+    }
+
+    #[test]
+    fn unchanged_update_is_not_recorded() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.update_task(1, |task| task.description = String::from("Notes"));
+        task_list.take_modified();
+
+        task_list.update_task(1, |task| task.description = String::from("Notes"));
+        assert!(!task_list.take_modified());
+        // Undo skips straight to the change before the unchanged update
+        task_list.undo();
+        assert_eq!(task_list.get_task(1).unwrap().description, "");
+    }
+
     #[test]
     fn toggle_execution_order() {
         let mut task_list = crate::task_list::tests::setup_task_list();
@@ -152,4 +502,29 @@ mod test {
             crate::task::ExecutionOrder::Parallel
         );
     }
+
+    #[test]
+    fn start_pause_and_complete_track_sessions() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+
+        task_list.start_task(1).pause_task(1).start_task(1);
+        let task = task_list.get_task(1).unwrap();
+        assert_eq!(task.sessions.len(), 2);
+        assert!(task.is_tracking());
+
+        // Completing stops the timer, and undo restarts it along with reopening the task
+        task_list.complete_task(1);
+        let task = task_list.get_task(1).unwrap();
+        assert!(!task.is_tracking());
+        assert!(task.completion_time().is_some());
+        assert!(task_list.actual_time(0) >= task_list.get_task(1).unwrap().active_time());
+
+        task_list.undo();
+        let task = task_list.get_task(1).unwrap();
+        assert!(task.is_tracking());
+        assert!(matches!(
+            task.task_status,
+            crate::task::TaskStatus::InProgress(_)
+        ));
+    }
 }
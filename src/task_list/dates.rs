@@ -0,0 +1,38 @@
+use crate::task_list::TaskList;
+
+impl TaskList {
+    /// Gets the nearest parent of a task that is due before the task is, or None if the task is
+    /// due on or before the due dates of all of its parents.
+    pub(crate) fn due_date_conflict(&self, pos: usize) -> Option<usize> {
+        let due_date = self.get_task(pos).ok()?.due_date?.date_naive();
+
+        let mut child = pos;
+        while let Some(parent) = self.get_parent(child) {
+            let parent_due = self.tasks[parent].due_date;
+            if parent_due.is_some_and(|parent_due| parent_due.date_naive() < due_date) {
+                return Some(parent);
+            }
+            child = parent;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local};
+
+    #[test]
+    fn subtasks_due_after_parents_conflict() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let now = Local::now();
+        task_list.update_task(0, |task| task.due_date = Some(now + Duration::days(2)));
+        task_list.update_task(1, |task| task.due_date = Some(now + Duration::days(1)));
+        task_list.update_task(2, |task| task.due_date = Some(now + Duration::days(3)));
+
+        assert_eq!(task_list.due_date_conflict(1), None);
+        assert_eq!(task_list.due_date_conflict(2), Some(0));
+        // Tasks without a due date never conflict
+        assert_eq!(task_list.due_date_conflict(3), None);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::task::{ExecutionOrder, Task, TaskStatus};
+use crate::task_list::TaskList;
+
+impl TaskList {
+    /// Calculate the total duration of the task and its subtasks depending on their execution order.
+    /// Returns the total duration in minutes.
+    pub(crate) fn calculate_task_duration(&self, pos: usize) -> i32 {
+        self.calculate_task_durations()
+            .get(pos)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Calculates the total duration of every task in the list, as `calculate_task_duration` does
+    /// for a single task.
+    pub(crate) fn calculate_task_durations(&self) -> Vec<i32> {
+        self.roll_up_durations(&|task| task.expected_duration.unwrap_or(0))
+    }
+
+    /// Calculates the estimated work left on every task and its subtasks in minutes. Complete tasks
+    /// have no work left, and the time already spent on a task is taken off its estimate.
+    pub(crate) fn calculate_remaining_durations(&self) -> Vec<i32> {
+        self.roll_up_durations(&remaining_work)
+    }
+
+    /// Gets the tasks that have taken longer than their estimate, along with the ratio of the
+    /// time spent to the estimate, from the largest overrun to the smallest.
+    pub(crate) fn overruns(&self) -> Vec<(usize, f64)> {
+        let mut overruns: Vec<(usize, f64)> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, task)| {
+                let estimate = task.expected_duration.filter(|&estimate| estimate > 0)?;
+                let actual = task.active_time().num_seconds() as f64 / 60.0;
+                let ratio = actual / estimate as f64;
+                (ratio > 1.0).then_some((pos, ratio))
+            })
+            .collect();
+        overruns.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        overruns
+    }
+
+    /// Rolls up a duration from the leaf tasks below every task. The durations of series subtasks
+    /// are added together, while only the longest of the parallel subtasks counts.
+    fn roll_up_durations(&self, leaf_duration: &dyn Fn(&Task) -> i32) -> Vec<i32> {
+        let mut durations = vec![0; self.len()];
+        // Subtasks are always below their parent, so working up the list from the bottom rolls up
+        // every subtask before its parent.
+        for pos in (0..self.len()).rev() {
+            let task = &self.tasks[pos];
+            let subtasks = self.get_direct_subtasks(pos);
+            durations[pos] = match (subtasks.is_empty(), &task.execution_order) {
+                (true, _) => leaf_duration(task),
+                // Saturate rather than overflow on estimates that are too long to add up
+                (false, ExecutionOrder::Series) => {
+                    subtasks.iter().fold(0, |total: i32, &subtask| {
+                        total.saturating_add(durations[subtask])
+                    })
+                }
+                (false, ExecutionOrder::Parallel) => subtasks
+                    .iter()
+                    .map(|&subtask| durations[subtask])
+                    .max()
+                    .unwrap_or(0),
+            };
+        }
+        durations
+    }
+}
+
+/// Gets the estimated work left on a single task in minutes, ignoring its subtasks.
+pub(crate) fn remaining_work(task: &Task) -> i32 {
+    match task.task_status {
+        TaskStatus::Complete(_) => 0,
+        _ => {
+            let spent = task.active_time().num_minutes() as i32;
+            (task.expected_duration.unwrap_or(0) - spent).max(0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::Session;
+    use chrono::{Duration, Local};
+
+    #[test]
+    fn remaining_duration_leaves_out_complete_work() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        for (pos, duration) in [(1, 10), (2, 30), (3, 20)] {
+            task_list.update_task(pos, |task| task.expected_duration = Some(duration));
+        }
+
+        assert_eq!(task_list.calculate_remaining_durations()[0], 60);
+        task_list.complete_task(2);
+        assert_eq!(task_list.calculate_remaining_durations()[0], 30);
+        assert_eq!(task_list.calculate_task_duration(0), 60);
+    }
+
+    #[test]
+    fn long_estimates_saturate() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        for pos in [1, 2] {
+            task_list.update_task(pos, |task| task.expected_duration = Some(i32::MAX));
+        }
+        assert_eq!(task_list.calculate_task_duration(0), i32::MAX);
+    }
+
+    #[test]
+    fn overruns_are_sorted_by_ratio() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let now = Local::now();
+        for (pos, estimate, spent) in [(1, 10, 15), (2, 30, 20), (5, 10, 30)] {
+            task_list.update_task(pos, |task| {
+                task.expected_duration = Some(estimate);
+                task.sessions = vec![Session {
+                    start: now - Duration::minutes(spent),
+                    end: Some(now),
+                }];
+            });
+        }
+
+        let overruns: Vec<usize> = task_list.overruns().iter().map(|&(pos, _)| pos).collect();
+        assert_eq!(overruns, vec![5, 1]);
+    }
+}
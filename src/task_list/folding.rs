@@ -0,0 +1,92 @@
+use crate::task_list::TaskList;
+
+impl TaskList {
+    /// Checks whether a task is visible, i.e. it is not hidden inside a folded task.
+    pub(crate) fn is_visible(&self, pos: usize) -> bool {
+        self.visible_tasks.binary_search(&pos).is_ok()
+    }
+
+    /// Gets the number of subtasks hidden by a folded task. Returns 0 if the task is not folded.
+    pub(crate) fn hidden_subtask_count(&self, pos: usize) -> usize {
+        match self.get_task(pos) {
+            Ok(task) if task.folded => self.get_last_subtask_pos(pos) - pos,
+            _ => 0,
+        }
+    }
+
+    /// Folds or unfolds the subtasks of a task. Tasks without subtasks cannot be folded.
+    pub fn toggle_fold(&mut self, pos: usize) -> &mut Self {
+        if pos >= self.len() || !self.has_subtasks(pos) {
+            return self;
+        }
+
+        self.tasks[pos].folded = !self.tasks[pos].folded;
+        self.mark_modified();
+        self.rebuild_visible_tasks();
+        self
+    }
+
+    /// Folds every task that has subtasks.
+    pub fn fold_all(&mut self) -> &mut Self {
+        self.fold_to_depth(0)
+    }
+
+    /// Unfolds every task.
+    pub fn unfold_all(&mut self) -> &mut Self {
+        self.fold_to_depth(i8::MAX)
+    }
+
+    /// Folds the tasks so that only the tasks above the given depth are visible. A depth of 1
+    /// shows only the root tasks.
+    pub fn fold_to_depth(&mut self, depth: i8) -> &mut Self {
+        for pos in 0..self.len() {
+            let folded = self.tasks[pos].depth >= depth - 1 && self.has_subtasks(pos);
+            self.tasks[pos].folded = folded;
+        }
+
+        self.mark_modified();
+        self.rebuild_visible_tasks();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn folded_subtasks_are_hidden() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.toggle_fold(0);
+
+        assert_eq!(task_list.visible_tasks, vec![0, 4, 5, 6, 7, 8]);
+        assert_eq!(task_list.hidden_subtask_count(0), 3);
+        assert!(!task_list.is_visible(2));
+
+        // Leaf tasks cannot be folded
+        task_list.toggle_fold(5);
+        assert_eq!(task_list.hidden_subtask_count(5), 0);
+    }
+
+    #[test]
+    fn fold_to_depth_shows_tasks_above_depth() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.add_new_subtask("Task 1.1.1", 1);
+
+        task_list.fold_to_depth(2);
+        assert_eq!(task_list.visible_tasks, vec![0, 1, 3, 4, 5, 6, 7, 8, 9]);
+
+        task_list.fold_all();
+        assert_eq!(task_list.visible_tasks, vec![0, 5, 9]);
+
+        task_list.unfold_all();
+        assert_eq!(task_list.visible_tasks, (0..10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn fold_state_survives_save_and_load() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.toggle_fold(4);
+
+        let loaded = crate::task_list::TaskList::from_json(&task_list.to_json().unwrap()).unwrap();
+        assert_eq!(loaded.visible_tasks, vec![0, 1, 2, 3, 4, 8]);
+    }
+}
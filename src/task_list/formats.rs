@@ -0,0 +1,96 @@
+use crate::task_list::TaskList;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use std::path::Path;
+use uuid::Uuid;
+
+/// A file format that task lists can be imported from or exported to.
+enum Format {
+    /// A nested Markdown checklist.
+    Markdown,
+    /// A todo.txt file with a line for each task.
+    TodoTxt,
+    /// An org-mode outline with a headline for each task.
+    Org,
+    /// An iCalendar file with a to-do for each dated task, which can only be exported.
+    ICalendar,
+    /// A Graphviz DOT digraph of the tasks, which can only be exported.
+    Dot,
+    /// A Mermaid flowchart of the tasks, which can only be exported.
+    Mermaid,
+}
+
+impl Format {
+    /// Gets the format of a file from its extension.
+    fn from_path(path: &Path) -> Result<Format> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("md") | Some("markdown") => Ok(Format::Markdown),
+            Some("txt") => Ok(Format::TodoTxt),
+            Some("org") => Ok(Format::Org),
+            Some("ics") => Ok(Format::ICalendar),
+            Some("dot") | Some("gv") => Ok(Format::Dot),
+            Some("mmd") | Some("mermaid") => Ok(Format::Mermaid),
+            _ => Err(eyre!(
+                "'{}' is not a supported file, use a .md, .txt, .org, .ics, .dot or .mmd file",
+                path.display()
+            )),
+        }
+    }
+}
+
+impl TaskList {
+    /// Exports the task list to a file in the format given by the extension of the path.
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let text = match Format::from_path(path)? {
+            Format::Markdown => self.to_markdown(),
+            Format::TodoTxt => self.to_todo_txt(),
+            Format::Org => self.to_org(),
+            Format::ICalendar => self.to_ical(),
+            Format::Dot => self.to_dot(),
+            Format::Mermaid => self.to_mermaid(),
+        };
+        std::fs::write(path, text)
+            .wrap_err_with(|| format!("Could not export to '{}'", path.display()))
+    }
+
+    /// Imports the tasks in a file in the format given by the extension of the path. Markdown
+    /// checklists and org-mode outlines are added to the end of the list, while todo.txt files are
+    /// merged into it.
+    pub fn import(&mut self, path: &Path) -> Result<()> {
+        let format = Format::from_path(path)?;
+        let read = || {
+            std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("Could not read '{}'", path.display()))
+        };
+        match format {
+            Format::Markdown => TaskList::from_markdown(&read()?).map(|imported| {
+                self.append(imported);
+            }),
+            Format::TodoTxt => self.merge_todo_txt(&read()?),
+            Format::Org => TaskList::from_org(&read()?).map(|imported| {
+                self.append(imported);
+            }),
+            Format::ICalendar | Format::Dot | Format::Mermaid => {
+                Err(eyre!("'{}' can only be exported to", path.display()))
+            }
+        }
+        .wrap_err_with(|| format!("Could not import '{}'", path.display()))
+    }
+
+    /// Adds the tasks of another list to the end of this list as new root tasks.
+    pub fn append(&mut self, mut other: TaskList) -> &mut Self {
+        // Give new ids to tasks that are already in the list, e.g. when a file is imported twice
+        for task in &mut other.tasks {
+            if self.tasks.iter().any(|existing| existing.id == task.id) {
+                task.id = Uuid::new_v4();
+            }
+        }
+        self.tasks.extend(other.tasks);
+        self.rebuild_all_indices();
+        self.mark_modified();
+        self
+    }
+}
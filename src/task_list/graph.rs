@@ -0,0 +1,185 @@
+use crate::task::{ExecutionOrder, TaskStatus};
+use crate::task_list::TaskList;
+
+/// The Mermaid class and fill colour of the tasks that are not started, in progress and complete.
+const STATUS_STYLES: [(&str, &str); 3] = [
+    ("notStarted", "#ffffff"),
+    ("inProgress", "#ffe08a"),
+    ("complete", "#b2f2bb"),
+];
+
+impl TaskList {
+    /// Writes the task list as a Graphviz DOT digraph. Nodes are coloured by the status of their
+    /// task, and tasks on the critical path are drawn in bold.
+    pub(crate) fn to_dot(&self) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n", escape_dot(&self.name));
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, style=\"rounded,filled\"];\n");
+        for (pos, task) in self.tasks.iter().enumerate() {
+            let mut attributes = vec![
+                format!("label=\"{}\"", escape_dot(&task.title)),
+                format!("fillcolor=\"{}\"", status_style(&task.task_status).1),
+            ];
+            if task.is_critical {
+                attributes.push(String::from("penwidth=2"));
+                attributes.push(String::from("fontname=\"Helvetica-Bold\""));
+            }
+            dot.push_str(&format!("  t{} [{}];\n", pos, attributes.join(", ")));
+        }
+        for (from, to) in self.graph_edges() {
+            dot.push_str(&format!("  t{} -> t{};\n", from, to));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes the task list as a Mermaid flowchart, with the same nodes and edges as `to_dot`.
+    pub(crate) fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart LR\n");
+        for (pos, task) in self.tasks.iter().enumerate() {
+            mermaid.push_str(&format!(
+                "  t{}[\"{}\"]\n",
+                pos,
+                escape_mermaid(&task.title)
+            ));
+        }
+        for (from, to) in self.graph_edges() {
+            mermaid.push_str(&format!("  t{} --> t{}\n", from, to));
+        }
+
+        for (class, colour) in STATUS_STYLES {
+            mermaid.push_str(&format!("  classDef {} fill:{}\n", class, colour));
+        }
+        mermaid.push_str("  classDef critical stroke-width:3px,font-weight:bold\n");
+
+        // Apply the classes to the nodes, leaving out classes that no task has
+        let class_nodes = |matches: &dyn Fn(usize) -> bool| -> Vec<String> {
+            (0..self.len())
+                .filter(|&pos| matches(pos))
+                .map(|pos| format!("t{}", pos))
+                .collect()
+        };
+        for (class, _) in STATUS_STYLES {
+            let nodes = class_nodes(&|pos| status_style(&self.tasks[pos].task_status).0 == class);
+            if !nodes.is_empty() {
+                mermaid.push_str(&format!("  class {} {}\n", nodes.join(","), class));
+            }
+        }
+        let critical = class_nodes(&|pos| self.tasks[pos].is_critical);
+        if !critical.is_empty() {
+            mermaid.push_str(&format!("  class {} critical\n", critical.join(",")));
+        }
+        mermaid
+    }
+
+    /// Gets the edges of the task graph. A task leads to its first subtask if they are executed in
+    /// series, or to every subtask if they are executed in parallel. Each series subtask then
+    /// leads to the next, with parallel subtasks joining again at the task that follows them.
+    fn graph_edges(&self) -> Vec<(usize, usize)> {
+        let mut edges = vec![];
+        for (pos, task) in self.tasks.iter().enumerate() {
+            let subtasks = self.get_direct_subtasks(pos);
+            let Some(&first) = subtasks.first() else {
+                continue;
+            };
+            match task.execution_order {
+                ExecutionOrder::Series => {
+                    edges.push((pos, first));
+                    for pair in subtasks.windows(2) {
+                        edges.extend(
+                            self.graph_exits(pair[0])
+                                .into_iter()
+                                .map(|exit| (exit, pair[1])),
+                        );
+                    }
+                }
+                ExecutionOrder::Parallel => {
+                    edges.extend(subtasks.iter().map(|&subtask| (pos, subtask)));
+                }
+            }
+        }
+        edges.sort();
+        edges
+    }
+
+    /// Gets the tasks that the work on a task finishes with, which lead on to the task that
+    /// follows it. These are the last leaf tasks of series subtasks, and the last leaf tasks of
+    /// every parallel subtask.
+    fn graph_exits(&self, pos: usize) -> Vec<usize> {
+        let subtasks = self.get_direct_subtasks(pos);
+        let Some(&last) = subtasks.last() else {
+            return vec![pos];
+        };
+        match self.tasks[pos].execution_order {
+            ExecutionOrder::Series => self.graph_exits(last),
+            ExecutionOrder::Parallel => subtasks
+                .iter()
+                .flat_map(|&subtask| self.graph_exits(subtask))
+                .collect(),
+        }
+    }
+}
+
+/// Gets the Mermaid class and fill colour of the tasks with a status.
+fn status_style(status: &TaskStatus) -> (&'static str, &'static str) {
+    match status {
+        TaskStatus::NotStarted => STATUS_STYLES[0],
+        TaskStatus::InProgress(_) => STATUS_STYLES[1],
+        TaskStatus::Complete(_) => STATUS_STYLES[2],
+    }
+}
+
+/// Escapes the characters that have a special meaning in a quoted DOT string.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes the quotes in a Mermaid node label.
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task_list::TaskList;
+
+    /// Sets up a series task with a parallel subtask followed by a leaf subtask.
+    fn setup_graph() -> TaskList {
+        let mut task_list = TaskList::new("Plan");
+        task_list.add_new_root_task_at_end("Launch");
+        task_list.add_new_subtask("Release", 0);
+        task_list.add_new_subtask("Build", 0);
+        task_list.add_new_subtask("Test", 1);
+        task_list.add_new_subtask("Compile", 1);
+        task_list.toggle_execution_order(1);
+        task_list
+    }
+
+    #[test]
+    fn parallel_subtasks_fan_out_and_join() {
+        let task_list = setup_graph();
+        assert_eq!(
+            task_list.graph_edges(),
+            vec![(0, 1), (1, 2), (1, 3), (2, 4), (3, 4)]
+        );
+    }
+
+    #[test]
+    fn graphs_are_styled_by_status() {
+        let mut task_list = setup_graph();
+        task_list.update_task(4, |task| task.expected_duration = Some(60));
+        task_list.complete_task(2);
+
+        let dot = task_list.to_dot();
+        assert!(dot.starts_with("digraph \"Plan\" {\n"));
+        assert!(dot.contains("  t2 [label=\"Compile\", fillcolor=\"#b2f2bb\"];\n"));
+        assert!(dot.contains("  t4 [label=\"Release\", fillcolor=\"#ffffff\", penwidth=2"));
+        assert!(dot.contains("  t3 -> t4;\n"));
+
+        let mermaid = task_list.to_mermaid();
+        assert!(mermaid.contains("  t2[\"Compile\"]\n"));
+        assert!(mermaid.contains("  t1 --> t3\n"));
+        assert!(mermaid.contains("  class t2 complete\n"));
+        assert!(mermaid.contains("  class t0,t4 critical\n"));
+    }
+}
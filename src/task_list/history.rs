@@ -0,0 +1,220 @@
+use crate::task::{ExecutionOrder, Task, TaskStatus};
+use crate::task_list::TaskList;
+
+/// The maximum number of changes that are kept in the undo history.
+const HISTORY_LIMIT: usize = 1000;
+
+/// A single reversible change to a task list. Each change holds just enough information to be
+/// applied again or reversed, so the history stays small even for large lists.
+#[derive(Debug, Clone)]
+pub(crate) enum Change {
+    /// Insert the tasks as a contiguous block starting at the position.
+    Insert { pos: usize, tasks: Vec<Task> },
+    /// Remove the contiguous block of tasks starting at the position.
+    Remove { pos: usize, tasks: Vec<Task> },
+    /// Add to the depth of the task at the position.
+    Depth { pos: usize, change: i8 },
+    /// Set the status of the task at the position.
+    Status {
+        pos: usize,
+        from: TaskStatus,
+        to: TaskStatus,
+    },
+    /// Set the title of the task at the position.
+    Title {
+        pos: usize,
+        from: String,
+        to: String,
+    },
+    /// Set the execution order of the subtasks of the task at the position.
+    Order {
+        pos: usize,
+        from: ExecutionOrder,
+        to: ExecutionOrder,
+    },
+    /// Replace the task at the position, for changes to any of its other fields.
+    Update {
+        pos: usize,
+        from: Box<Task>,
+        to: Box<Task>,
+    },
+    /// Apply several changes in order as a single step.
+    Batch(Vec<Change>),
+}
+
+impl Change {
+    /// Gets the change that reverses this one.
+    fn inverse(&self) -> Change {
+        match self.clone() {
+            Change::Insert { pos, tasks } => Change::Remove { pos, tasks },
+            Change::Remove { pos, tasks } => Change::Insert { pos, tasks },
+            Change::Depth { pos, change } => Change::Depth {
+                pos,
+                change: -change,
+            },
+            Change::Status { pos, from, to } => Change::Status {
+                pos,
+                from: to,
+                to: from,
+            },
+            Change::Title { pos, from, to } => Change::Title {
+                pos,
+                from: to,
+                to: from,
+            },
+            Change::Order { pos, from, to } => Change::Order {
+                pos,
+                from: to,
+                to: from,
+            },
+            Change::Update { pos, from, to } => Change::Update {
+                pos,
+                from: to,
+                to: from,
+            },
+            Change::Batch(changes) => {
+                Change::Batch(changes.iter().rev().map(Change::inverse).collect())
+            }
+        }
+    }
+
+    /// Gets the position of the task most affected by the change.
+    fn pos(&self) -> usize {
+        match *self {
+            Change::Insert { pos, .. }
+            | Change::Remove { pos, .. }
+            | Change::Depth { pos, .. }
+            | Change::Status { pos, .. }
+            | Change::Title { pos, .. }
+            | Change::Order { pos, .. }
+            | Change::Update { pos, .. } => pos,
+            Change::Batch(ref changes) => changes.first().map_or(0, Change::pos),
+        }
+    }
+}
+
+/// The undo and redo stacks of changes made to a task list.
+#[derive(Debug, Default)]
+pub(crate) struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+impl TaskList {
+    /// Applies a change to the list and records it in the history so that it can be undone.
+    pub(crate) fn execute(&mut self, change: Change) {
+        self.apply(&change);
+
+        let history = &mut self.history;
+        history.redo.clear();
+        history.undo.push(change);
+        if history.undo.len() > HISTORY_LIMIT {
+            history.undo.remove(0);
+        }
+    }
+
+    /// Reverses the most recent change. Returns the position of the affected task, or None if
+    /// there is nothing to undo.
+    pub fn undo(&mut self) -> Option<usize> {
+        let change = self.history.undo.pop()?;
+        self.apply(&change.inverse());
+        let pos = change.pos();
+        self.history.redo.push(change);
+        Some(pos)
+    }
+
+    /// Applies the most recently undone change again. Returns the position of the affected task,
+    /// or None if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<usize> {
+        let change = self.history.redo.pop()?;
+        self.apply(&change);
+        let pos = change.pos();
+        self.history.undo.push(change);
+        Some(pos)
+    }
+
+    /// Applies a change to the list without recording it.
+    fn apply(&mut self, change: &Change) {
+        self.apply_tasks(change);
+        self.mark_modified();
+        self.rebuild_all_indices();
+    }
+
+    /// Applies a change to the tasks without rebuilding any indices.
+    fn apply_tasks(&mut self, change: &Change) {
+        match change {
+            Change::Insert { pos, tasks } => {
+                self.tasks.splice(*pos..*pos, tasks.iter().cloned());
+            }
+            Change::Remove { pos, tasks } => {
+                self.tasks.drain(*pos..*pos + tasks.len());
+            }
+            Change::Depth { pos, change } => self.tasks[*pos].depth += change,
+            Change::Status { pos, to, .. } => self.tasks[*pos].task_status = to.clone(),
+            Change::Title { pos, to, .. } => self.tasks[*pos].title = to.clone(),
+            Change::Order { pos, to, .. } => self.tasks[*pos].execution_order = to.clone(),
+            Change::Update { pos, to, .. } => self.tasks[*pos] = (**to).clone(),
+            Change::Batch(changes) => {
+                for change in changes {
+                    self.apply_tasks(change);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::ExecutionOrder;
+    use crate::task_list::Direction;
+
+    #[test]
+    fn undo_delete_restores_subtree() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let expected = task_list.print_debug();
+
+        task_list.delete_task(4);
+        assert_eq!(task_list.len(), 5);
+
+        assert_eq!(task_list.undo(), Some(4));
+        assert_eq!(task_list.print_debug(), expected);
+        assert_eq!(task_list.get_direct_subtasks(4), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn undo_and_redo_reverse_each_other() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let original = task_list.print_debug();
+
+        task_list.demote_task(4);
+        task_list.move_tasks(1, 1, &Direction::Down).unwrap();
+        task_list.toggle_execution_order(0);
+        task_list.toggle_task_status(2);
+        let changed = task_list.print_debug();
+
+        for _ in 0..4 {
+            task_list.undo();
+        }
+        assert_eq!(task_list.print_debug(), original);
+        assert_eq!(
+            task_list.get_task(0).unwrap().execution_order,
+            ExecutionOrder::Series
+        );
+
+        while task_list.redo().is_some() {}
+        assert_eq!(task_list.print_debug(), changed);
+        assert_eq!(
+            task_list.get_task(0).unwrap().execution_order,
+            ExecutionOrder::Parallel
+        );
+    }
+
+    #[test]
+    fn new_change_clears_redo() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.delete_task(8);
+        task_list.undo();
+        task_list.add_new_root_task_at_end("Task 4");
+        assert_eq!(task_list.redo(), None);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::task::TaskStatus;
+use crate::task_list::TaskList;
+use chrono::{DateTime, Local, Utc};
+
+/// The longest that a line of an iCalendar file can be in bytes before it is folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+impl TaskList {
+    /// Writes the tasks that have a start or due date as the to-dos of an iCalendar file. Each
+    /// to-do is related to the to-do of its nearest parent task that is exported so that calendar
+    /// clients can show the hierarchy. The estimate of a task is only written as its duration
+    /// when the task has a start date and no due date, as a to-do can't have both a due date and
+    /// a duration.
+    pub(crate) fn to_ical(&self) -> String {
+        let mut lines = vec![
+            String::from("BEGIN:VCALENDAR"),
+            String::from("VERSION:2.0"),
+            String::from("PRODID:-//nick-boey//Subtask//EN"),
+        ];
+        let now = format_utc(&Local::now());
+
+        let is_dated =
+            |pos: usize| self.tasks[pos].start_date.is_some() || self.tasks[pos].due_date.is_some();
+        for (pos, task) in self.tasks.iter().enumerate() {
+            if !is_dated(pos) {
+                continue;
+            }
+
+            lines.push(String::from("BEGIN:VTODO"));
+            lines.push(format!("UID:{}", task.id));
+            lines.push(format!("DTSTAMP:{}", now));
+            lines.push(format!("CREATED:{}", format_utc(&task.creation_date)));
+            lines.push(format!("SUMMARY:{}", escape_text(&task.title)));
+            if !task.description.is_empty() {
+                lines.push(format!("DESCRIPTION:{}", escape_text(&task.description)));
+            }
+            if let Some(start_date) = task.start_date {
+                lines.push(format!("DTSTART:{}", format_utc(&start_date)));
+            }
+            match (task.due_date, task.start_date, task.expected_duration) {
+                (Some(due_date), _, _) => lines.push(format!("DUE:{}", format_utc(&due_date))),
+                (None, Some(_), Some(estimate)) => {
+                    lines.push(format!("DURATION:{}", format_duration(estimate)))
+                }
+                _ => {}
+            }
+            lines.push(format!(
+                "STATUS:{}",
+                match task.task_status {
+                    TaskStatus::NotStarted => "NEEDS-ACTION",
+                    TaskStatus::InProgress(_) => "IN-PROCESS",
+                    TaskStatus::Complete(_) => "COMPLETED",
+                }
+            ));
+            if let TaskStatus::Complete(time) = task.task_status {
+                lines.push(format!("COMPLETED:{}", format_utc(&time)));
+            }
+            let mut parent = self.get_parent(pos);
+            while let Some(undated) = parent.filter(|&parent| !is_dated(parent)) {
+                parent = self.get_parent(undated);
+            }
+            if let Some(parent) = parent {
+                lines.push(format!("RELATED-TO:{}", self.tasks[parent].id));
+            }
+            lines.push(String::from("END:VTODO"));
+        }
+        lines.push(String::from("END:VCALENDAR"));
+
+        lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+    }
+}
+
+/// Formats a time as an iCalendar date and time in UTC, e.g. `20250110T090000Z`.
+fn format_utc(time: &DateTime<Local>) -> String {
+    time.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Formats a duration in minutes as an iCalendar duration, e.g. `PT1H30M`.
+fn format_duration(minutes: i32) -> String {
+    let minutes = minutes.max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("PT{}M", minutes),
+        (hours, 0) => format!("PT{}H", hours),
+        (hours, minutes) => format!("PT{}H{}M", hours, minutes),
+    }
+}
+
+/// Escapes the characters that have a special meaning in iCalendar text values.
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a line that is longer than the iCalendar limit onto continuation lines that start with
+/// a space, without splitting any characters.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the length of the continuation line
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task_list::ical::fold_line;
+    use chrono::{Local, TimeZone};
+
+    #[test]
+    fn dated_tasks_are_exported_as_related_todos() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let date = Local.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap();
+        task_list.update_task(0, |task| task.due_date = Some(date));
+        task_list.update_task(2, |task| {
+            task.title = String::from("Write docs, tests");
+            task.start_date = Some(date);
+            task.expected_duration = Some(90);
+        });
+
+        let ical = task_list.to_ical();
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("BEGIN:VTODO").count(), 2);
+
+        let parent_id = task_list.tasks[0].id;
+        let todo = ical.split("BEGIN:VTODO").nth(2).unwrap();
+        assert!(todo.contains(&format!("UID:{}\r\n", task_list.tasks[2].id)));
+        assert!(todo.contains("SUMMARY:Write docs\\, tests\r\n"));
+        assert!(todo.contains("DURATION:PT1H30M\r\n"));
+        assert!(todo.contains("STATUS:NEEDS-ACTION\r\n"));
+        assert!(todo.contains(&format!("RELATED-TO:{}\r\n", parent_id)));
+    }
+
+    #[test]
+    fn todos_are_related_to_their_nearest_exported_parent() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.add_new_subtask("Task 1.1.1", 1);
+        let date = Local.with_ymd_and_hms(2025, 1, 10, 9, 0, 0).unwrap();
+        for pos in [0, 2, 6] {
+            task_list.update_task(pos, |task| task.due_date = Some(date));
+        }
+
+        let ical = task_list.to_ical();
+        let todos: Vec<&str> = ical.split("BEGIN:VTODO").skip(1).collect();
+        assert_eq!(todos.len(), 3);
+        // Task 1.1 has no dates, so Task 1.1.1 is related to Task 1
+        assert!(todos[1].contains(&format!("RELATED-TO:{}\r\n", task_list.tasks[0].id)));
+        // Task 2 has no dates and no parent, so Task 2.1 isn't related to anything
+        assert!(!todos[2].contains("RELATED-TO"));
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold_line(&line);
+        let lines: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() <= 75));
+        assert!(lines[1].starts_with(' '));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,4 +1,4 @@
-﻿use crate::task::ExecutionOrder;
+﻿use crate::task::{ExecutionOrder, TaskStatus};
 use crate::task_list::TaskList;
 
 impl TaskList {
@@ -7,6 +7,8 @@ impl TaskList {
         self.rebuild_depth_index();
         self.rebuild_title_index();
         self.rebuild_next_tasks();
+        self.rebuild_critical_tasks();
+        self.rebuild_visible_tasks();
         self
     }
 
@@ -32,6 +34,16 @@ impl TaskList {
         self
     }
 
+    /// Checks whether a task can be worked on now, i.e. it is up next and none of its subtasks
+    /// are.
+    pub(crate) fn is_actionable(&self, pos: usize) -> bool {
+        self.next_tasks.contains(&pos)
+            && !self
+                .get_direct_subtasks(pos)
+                .iter()
+                .any(|subtask| self.next_tasks.contains(subtask))
+    }
+
     /// Rebuilds the index of tasks that are up next.
     pub(crate) fn rebuild_next_tasks(&mut self) -> &mut Self {
         self.next_tasks.clear();
@@ -47,12 +59,62 @@ impl TaskList {
         self
     }
 
-    /// Gets all the next subtasks of the current position
+    /// Marks the tasks on the critical path of each root task, i.e. the tasks that determine the
+    /// total duration of the root. Every subtask of a series task is on the critical path, but only
+    /// the longest subtasks of a parallel task are. Tasks that add nothing to the duration are
+    /// never critical.
+    pub(crate) fn rebuild_critical_tasks(&mut self) -> &mut Self {
+        let durations = self.calculate_task_durations();
+
+        // Walk down from each root task, following only the subtasks on the critical path.
+        for task in self.tasks.iter_mut() {
+            task.is_critical = false;
+        }
+        let mut stack = self.depth_index.get(&0).cloned().unwrap_or_default();
+        while let Some(pos) = stack.pop() {
+            if durations[pos] == 0 {
+                continue;
+            }
+            self.tasks[pos].is_critical = true;
+
+            let longest = durations[pos];
+            let subtasks = self.get_direct_subtasks(pos);
+            stack.extend(subtasks.into_iter().filter(|&subtask| {
+                match self.tasks[pos].execution_order {
+                    ExecutionOrder::Series => true,
+                    ExecutionOrder::Parallel => durations[subtask] == longest,
+                }
+            }));
+        }
+        self
+    }
+
+    /// Rebuilds the index of tasks that are not hidden inside a folded task.
+    pub(crate) fn rebuild_visible_tasks(&mut self) -> &mut Self {
+        self.visible_tasks.clear();
+        // The depth of the folded task that is currently hiding its subtasks, if there is one
+        let mut folded_depth: Option<i8> = None;
+        for (i, task) in self.tasks.iter().enumerate() {
+            if folded_depth.is_some_and(|depth| task.depth > depth) {
+                continue;
+            }
+            folded_depth = task.folded.then_some(task.depth);
+            self.visible_tasks.push(i);
+        }
+        self
+    }
+
+    /// Gets all the next subtasks of the current position. Complete tasks and their subtasks are
+    /// skipped, so a series of subtasks advances to its first incomplete subtask and a task whose
+    /// subtasks are all complete is left as the next task itself.
     fn get_next_subtasks(&self, pos: usize) -> Vec<usize> {
         let mut next_subtasks: Vec<usize> = vec![];
         let Ok(task) = self.get_task(pos) else {
             return next_subtasks;
         };
+        if let TaskStatus::Complete(_) = task.task_status {
+            return next_subtasks;
+        }
 
         next_subtasks.push(pos);
 
@@ -63,10 +125,14 @@ impl TaskList {
         // Get the direct subtasks of this task
         let subtasks = self.get_direct_subtasks(pos);
         match task.execution_order {
-            // If in series, just add the subtasks of the first task to the next tasks
+            // If in series, just add the subtasks of the first incomplete task to the next tasks
             ExecutionOrder::Series => {
-                if let Some(first_subtask) = subtasks.first() {
-                    next_subtasks.append(&mut self.get_next_subtasks(*first_subtask));
+                if let Some(mut first_subtasks) = subtasks
+                    .into_iter()
+                    .map(|subtask| self.get_next_subtasks(subtask))
+                    .find(|subtasks| !subtasks.is_empty())
+                {
+                    next_subtasks.append(&mut first_subtasks);
                 }
             }
             // If in parallel, add all the subtasks of the first task to the next tasks
@@ -0,0 +1,315 @@
+use crate::task::{ExecutionOrder, Task, TaskStatus};
+use crate::task_list::TaskList;
+use chrono::Local;
+use color_eyre::eyre::{eyre, Result};
+
+/// The number of spaces that each level of subtasks is indented by.
+const INDENT: usize = 2;
+/// The marker after the title of a task whose subtasks are executed in parallel.
+const PARALLEL_MARKER: &str = "(parallel)";
+
+impl TaskList {
+    /// Writes the task list as a nested Markdown checklist, under a heading with the name of the
+    /// list if it has one. Each level of subtasks is indented by two spaces, in progress tasks are
+    /// marked with `[/]`, and descriptions are indented below the task they belong to.
+    pub(crate) fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        if !self.name.is_empty() {
+            markdown.push_str(&format!("# {}\n\n", self.name));
+        }
+        for task in &self.tasks {
+            let indent = " ".repeat(task.depth as usize * INDENT);
+            let check = match task.task_status {
+                TaskStatus::NotStarted => ' ',
+                TaskStatus::InProgress(_) => '/',
+                TaskStatus::Complete(_) => 'x',
+            };
+            markdown.push_str(&format!(
+                "{}- [{}] {}",
+                indent,
+                check,
+                escape_title(&task.title)
+            ));
+            if task.execution_order == ExecutionOrder::Parallel {
+                markdown.push_str(&format!(" {}", PARALLEL_MARKER));
+            }
+            markdown.push('\n');
+
+            for line in task.description.lines() {
+                if line.trim().is_empty() {
+                    markdown.push('\n');
+                    continue;
+                }
+                // Escape lines that would otherwise be read back as tasks
+                let content = line.trim_start();
+                let leading = &line[..line.len() - content.len()];
+                let escape = match parse_item(content).is_some() || content.starts_with('\\') {
+                    true => "\\",
+                    false => "",
+                };
+                markdown.push_str(&format!(
+                    "{}{}{}{}{}\n",
+                    indent,
+                    " ".repeat(INDENT),
+                    leading,
+                    escape,
+                    content
+                ));
+            }
+        }
+        markdown
+    }
+
+    /// Reads a task list from a nested Markdown checklist as written by `to_markdown`. Returns an
+    /// error with the line number of the first line that is not a valid task or description.
+    pub(crate) fn from_markdown(markdown: &str) -> Result<TaskList> {
+        let mut name = String::new();
+        let mut tasks: Vec<Task> = vec![];
+        // Blank lines are only kept in a description when more of the description follows them
+        let mut blank_lines = 0;
+
+        for (index, line) in markdown.lines().enumerate() {
+            let number = index + 1;
+            if line.trim().is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            // Only spaces count towards the indent, so that any other whitespace is kept in
+            // descriptions
+            let content = line.trim_start_matches(' ');
+            let indent = line.len() - content.len();
+            if content.starts_with(char::is_whitespace)
+                && parse_item(content.trim_start()).is_some()
+            {
+                return Err(eyre!(
+                    "line {}: indent with spaces rather than tabs",
+                    number
+                ));
+            }
+
+            if let Some((check, title)) = parse_item(content) {
+                if indent % INDENT != 0 {
+                    return Err(eyre!(
+                        "line {}: indented by {} spaces, which is not a multiple of {}",
+                        number,
+                        indent,
+                        INDENT
+                    ));
+                }
+                let depth = indent / INDENT;
+                let max_depth = tasks.last().map_or(0, |task| task.depth as usize + 1);
+                if depth > max_depth {
+                    return Err(eyre!(
+                        "line {}: '{}' is indented more than one level below the task above it",
+                        number,
+                        title
+                    ));
+                }
+
+                let (title, execution_order) = match title.strip_suffix(PARALLEL_MARKER) {
+                    Some(title) if !title.ends_with('\\') => {
+                        (title.trim_end(), ExecutionOrder::Parallel)
+                    }
+                    _ => (title, ExecutionOrder::Series),
+                };
+                let mut task = Task::new(&unescape_title(title), depth as i8);
+                task.execution_order = execution_order;
+                task.task_status = match check {
+                    ' ' => TaskStatus::NotStarted,
+                    '/' => TaskStatus::InProgress(Local::now()),
+                    'x' | 'X' => TaskStatus::Complete(Local::now()),
+                    _ => {
+                        return Err(eyre!(
+                            "line {}: '[{}]' is not a status, use '[ ]', '[/]' or '[x]'",
+                            number,
+                            check
+                        ))
+                    }
+                };
+                tasks.push(task);
+                blank_lines = 0;
+                continue;
+            }
+
+            let Some(task) = tasks.last_mut() else {
+                // The list can be named by a heading above the first task
+                match content.strip_prefix("# ") {
+                    Some(heading) if indent == 0 && name.is_empty() => {
+                        name = heading.trim().to_string();
+                        continue;
+                    }
+                    _ => {
+                        return Err(eyre!(
+                            "line {}: expected a task such as '- [ ] Title'",
+                            number
+                        ))
+                    }
+                }
+            };
+
+            let description_indent = (task.depth as usize + 1) * INDENT;
+            if indent < description_indent {
+                return Err(eyre!(
+                    "line {}: the description of '{}' must be indented by {} spaces",
+                    number,
+                    task.title,
+                    description_indent
+                ));
+            }
+            let line = &line[description_indent..];
+            let content = line.trim_start();
+            let leading = &line[..line.len() - content.len()];
+            let content = content.strip_prefix('\\').unwrap_or(content);
+
+            if !task.description.is_empty() {
+                task.description.push_str(&"\n".repeat(blank_lines + 1));
+            }
+            task.description.push_str(leading);
+            task.description.push_str(content);
+            blank_lines = 0;
+        }
+
+        let mut task_list = TaskList::new(&name);
+        task_list.tasks = tasks;
+        task_list.rebuild_all_indices();
+        Ok(task_list)
+    }
+}
+
+/// Escapes the parallel marker at the end of a title with a backslash, so that it is not read
+/// back as the execution order of the task.
+fn escape_title(title: &str) -> String {
+    match title.strip_suffix(PARALLEL_MARKER) {
+        Some(title) => format!("{}\\{}", title, PARALLEL_MARKER),
+        None => title.to_string(),
+    }
+}
+
+/// Takes off the backslash that `escape_title` puts before a parallel marker in a title.
+fn unescape_title(title: &str) -> String {
+    match title.strip_suffix(PARALLEL_MARKER) {
+        Some(title) => format!(
+            "{}{}",
+            title.strip_suffix('\\').unwrap_or(title),
+            PARALLEL_MARKER
+        ),
+        None => title.to_string(),
+    }
+}
+
+/// Splits a Markdown checklist item such as `- [x] Title` into its check mark and title, or
+/// returns None if the line is not a checklist item.
+fn parse_item(content: &str) -> Option<(char, &str)> {
+    let rest = content
+        .strip_prefix("- [")
+        .or_else(|| content.strip_prefix("* ["))?;
+    let mut chars = rest.chars();
+    let check = chars.next()?;
+    let title = chars.as_str().strip_prefix(']')?;
+    if !title.is_empty() && !title.starts_with(' ') {
+        return None;
+    }
+    Some((check, title.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::{ExecutionOrder, TaskStatus};
+    use crate::task_list::TaskList;
+
+    #[test]
+    fn markdown_round_trips() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.toggle_execution_order(4);
+        task_list.start_task(1);
+        task_list.update_task(2, |task| {
+            task.description = String::from("First line\nSecond line\n\n- [ ] Not a task")
+        });
+        task_list.complete_task(8);
+
+        let markdown = task_list.to_markdown();
+        assert!(markdown.contains("\n  - [/] Task 1.1\n"));
+        assert!(markdown.contains("\n- [ ] Task 2 (parallel)\n"));
+        assert!(markdown.contains("\n    \\- [ ] Not a task\n"));
+
+        let imported = TaskList::from_markdown(&markdown).unwrap();
+        assert_eq!(imported.name, "Task List");
+        assert_eq!(imported.to_markdown(), markdown);
+        assert_eq!(imported.len(), task_list.len());
+        assert_eq!(
+            imported.tasks[2].description,
+            task_list.tasks[2].description
+        );
+        assert_eq!(imported.tasks[4].execution_order, ExecutionOrder::Parallel);
+        assert!(matches!(
+            imported.tasks[8].task_status,
+            TaskStatus::Complete(_)
+        ));
+        assert_eq!(
+            imported.depth_index.get(&1).unwrap(),
+            &vec![1, 2, 3, 5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn titles_ending_with_the_parallel_marker_round_trip() {
+        let mut task_list = TaskList::new("");
+        task_list.add_new_root_task_at_end("Run checks (parallel)");
+        task_list.add_new_root_task_at_end("Build \\(parallel)");
+        task_list.toggle_execution_order(1);
+
+        let markdown = task_list.to_markdown();
+        assert_eq!(
+            markdown,
+            "- [ ] Run checks \\(parallel)\n- [ ] Build \\\\(parallel) (parallel)\n"
+        );
+        let imported = TaskList::from_markdown(&markdown).unwrap();
+        assert_eq!(imported.tasks[0].title, "Run checks (parallel)");
+        assert_eq!(imported.tasks[0].execution_order, ExecutionOrder::Series);
+        assert_eq!(imported.tasks[1].title, "Build \\(parallel)");
+        assert_eq!(imported.tasks[1].execution_order, ExecutionOrder::Parallel);
+    }
+
+    #[test]
+    fn malformed_markdown_gives_line_numbers() {
+        let error = |markdown: &str| TaskList::from_markdown(markdown).unwrap_err().to_string();
+
+        assert_eq!(
+            error("- [ ] Task 1\n    - [ ] Task 1.1.1"),
+            "line 2: 'Task 1.1.1' is indented more than one level below the task above it"
+        );
+        assert_eq!(
+            error("- [ ] Task 1\n   - [ ] Task 1.1"),
+            "line 2: indented by 3 spaces, which is not a multiple of 2"
+        );
+        assert_eq!(
+            error("Notes\n- [ ] Task 1"),
+            "line 1: expected a task such as '- [ ] Title'"
+        );
+        assert_eq!(
+            error("- [ ] Task 1\n\n- [?] Task 2"),
+            "line 3: '[?]' is not a status, use '[ ]', '[/]' or '[x]'"
+        );
+        assert_eq!(
+            error("- [ ] Task 1\n\t- [ ] Task 1.1"),
+            "line 2: indent with spaces rather than tabs"
+        );
+        assert_eq!(
+            error("- [ ] Task 1\n\u{3000}Notes"),
+            "line 2: the description of 'Task 1' must be indented by 2 spaces"
+        );
+    }
+
+    #[test]
+    fn other_leading_whitespace_is_kept_in_descriptions() {
+        let task_list = TaskList::from_markdown("- [ ] Task 1\n  \u{3000}Notes\n  \tCode").unwrap();
+        assert_eq!(task_list.tasks[0].description, "\u{3000}Notes\n\tCode");
+        assert_eq!(
+            TaskList::from_markdown(&task_list.to_markdown())
+                .unwrap()
+                .tasks[0]
+                .description,
+            task_list.tasks[0].description
+        );
+    }
+}
@@ -0,0 +1,543 @@
+use crate::task::{ExecutionOrder, Task, TaskStatus};
+use crate::task_list::TaskList;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use color_eyre::eyre::{eyre, Result};
+use uuid::Uuid;
+
+/// The keywords of the planning line below a headline.
+const PLANNING_KEYWORDS: [&str; 3] = ["CLOSED:", "SCHEDULED:", "DEADLINE:"];
+/// The `#+TODO:` setting that declares the keywords written for each task status.
+const TODO_SETTING: &str = "#+TODO: TODO DOING | DONE";
+
+/// The part of the section below a headline that is being read. The planning line and property
+/// drawer are only read where they are written, so a body that starts like them is kept as it is.
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    /// The line directly below the headline, which may be the planning line.
+    Planning,
+    /// The line where the property drawer may start.
+    Drawer,
+    /// The lines inside the property drawer.
+    Properties,
+    /// The body of the headline.
+    Body,
+}
+
+/// The TODO keywords of an outline, as declared by its `#+TODO:` settings.
+struct TodoKeywords {
+    not_started: Vec<String>,
+    in_progress: Vec<String>,
+    complete: Vec<String>,
+}
+
+impl Default for TodoKeywords {
+    fn default() -> Self {
+        TodoKeywords {
+            not_started: vec![String::from("TODO")],
+            in_progress: vec![String::from("DOING")],
+            complete: vec![String::from("DONE")],
+        }
+    }
+}
+
+impl TaskList {
+    /// Writes the task list as an org-mode outline, with a headline for each task. The subtasks of
+    /// series tasks are marked as `:ORDERED: t` and parallel tasks as `:ORDERED: nil`, and the id,
+    /// estimate and any properties kept from an imported file are written to the property drawer
+    /// of each headline.
+    pub(crate) fn to_org(&self) -> String {
+        let mut org = String::new();
+        if !self.name.is_empty() {
+            org.push_str(&format!("#+TITLE: {}\n", self.name));
+        }
+        // Declare the keywords, as org-mode only knows TODO and DONE by default
+        org.push_str(&format!("{}\n\n", TODO_SETTING));
+
+        for (pos, task) in self.tasks.iter().enumerate() {
+            let level = task.depth as usize + 1;
+            let keyword = match task.task_status {
+                TaskStatus::NotStarted => "TODO",
+                TaskStatus::InProgress(_) => "DOING",
+                TaskStatus::Complete(_) => "DONE",
+            };
+            org.push_str(&format!(
+                "{} {} {}\n",
+                "*".repeat(level),
+                keyword,
+                task.title
+            ));
+            // Indent the body below the stars so that it can't be read as a headline
+            let indent = " ".repeat(level + 1);
+
+            let mut planning = vec![];
+            if let TaskStatus::Complete(time) = task.task_status {
+                planning.push(format!("CLOSED: [{}]", time.format("%Y-%m-%d %a %H:%M")));
+            }
+            if let Some(start_date) = task.start_date {
+                planning.push(format!("SCHEDULED: {}", format_timestamp(&start_date)));
+            }
+            if let Some(due_date) = task.due_date {
+                planning.push(format!("DEADLINE: {}", format_timestamp(&due_date)));
+            }
+            if !planning.is_empty() {
+                org.push_str(&format!("{}{}\n", indent, planning.join(" ")));
+            }
+
+            let mut properties = vec![(String::from("ID"), task.id.to_string())];
+            // Parallel tasks are marked even without subtasks, as tasks are in series by default
+            match task.execution_order {
+                ExecutionOrder::Series if self.has_subtasks(pos) => {
+                    properties.push((String::from("ORDERED"), String::from("t")));
+                }
+                ExecutionOrder::Series => {}
+                ExecutionOrder::Parallel => {
+                    properties.push((String::from("ORDERED"), String::from("nil")));
+                }
+            }
+            if let Some(estimate) = task.expected_duration {
+                let effort = format!("{}:{:02}", estimate / 60, estimate % 60);
+                properties.push((String::from("EFFORT"), effort));
+            }
+            properties.extend(task.properties.iter().cloned());
+            org.push_str(&format!("{}:PROPERTIES:\n", indent));
+            for (key, value) in properties {
+                org.push_str(&format!("{}:{}: {}\n", indent, key, value));
+            }
+            org.push_str(&format!("{}:END:\n", indent));
+
+            for line in task.description.lines() {
+                match line.trim().is_empty() {
+                    true => org.push('\n'),
+                    false => org.push_str(&format!("{}{}\n", indent, line)),
+                }
+            }
+        }
+        org
+    }
+
+    /// Reads a task list from an org-mode outline. Headlines marked `:ORDERED: nil`, or with
+    /// subtasks and no `:ORDERED:` property, are executed in parallel, and properties that aren't
+    /// used by tasks are kept on the task. The keywords of the headlines are read using the
+    /// `#+TODO:` settings of the outline, or as TODO, DOING and DONE if it has none. Returns an
+    /// error with the line number of the first line that is not valid.
+    pub(crate) fn from_org(org: &str) -> Result<TaskList> {
+        let mut name = String::new();
+        let mut keywords: Option<TodoKeywords> = None;
+        let mut tasks: Vec<Task> = vec![];
+        let mut ordered: Vec<Option<bool>> = vec![];
+        let mut section = Section::Body;
+        // Blank lines are only kept in a description when more of the description follows them
+        let mut blank_lines = 0;
+
+        for (index, line) in org.lines().enumerate() {
+            let number = index + 1;
+
+            if let Some((level, headline)) = parse_headline(line) {
+                let max_level = tasks.last().map_or(1, |task| task.depth as usize + 2);
+                if level > max_level {
+                    return Err(eyre!(
+                        "line {}: '{}' is more than one level below the headline above it",
+                        number,
+                        headline
+                    ));
+                }
+                let keywords = keywords.get_or_insert_with(TodoKeywords::default);
+                let (keyword, title) = headline.split_once(' ').unwrap_or((headline, ""));
+                let (task_status, title) = match keywords.status(keyword) {
+                    Some(_) if title.trim().is_empty() => {
+                        return Err(eyre!("line {}: the headline has no title", number))
+                    }
+                    Some(task_status) => (task_status, title),
+                    None => (TaskStatus::NotStarted, headline),
+                };
+
+                let mut task = Task::new(title.trim(), (level - 1) as i8);
+                task.task_status = task_status;
+                tasks.push(task);
+                ordered.push(None);
+                section = Section::Planning;
+                blank_lines = 0;
+                continue;
+            }
+
+            let Some(task) = tasks.last_mut() else {
+                // Only the title and keywords are read from the settings above the first headline
+                if let Some(title) = line.strip_prefix("#+TITLE:") {
+                    name = title.trim().to_string();
+                } else if let Some(setting) = line.strip_prefix("#+TODO:") {
+                    let parsed = TodoKeywords::parse(setting).ok_or_else(|| {
+                        eyre!(
+                            "line {}: expected keywords such as '{}'",
+                            number,
+                            TODO_SETTING
+                        )
+                    })?;
+                    match &mut keywords {
+                        Some(keywords) => keywords.extend(parsed),
+                        None => keywords = Some(parsed),
+                    }
+                }
+                continue;
+            };
+            let content = line.trim();
+
+            if section == Section::Properties {
+                if content.eq_ignore_ascii_case(":END:") {
+                    section = Section::Body;
+                    continue;
+                }
+                let Some((key, value)) = parse_property(content) else {
+                    return Err(eyre!(
+                        "line {}: expected a property such as ':KEY: value'",
+                        number
+                    ));
+                };
+                let effort = parse_effort(value);
+                match (key.to_uppercase().as_str(), Uuid::parse_str(value)) {
+                    ("ID", Ok(id)) => task.id = id,
+                    ("ORDERED", _) => {
+                        if let Some(ordered) = ordered.last_mut() {
+                            *ordered = Some(value == "t");
+                        }
+                    }
+                    ("EFFORT", _) if effort.is_some() => task.expected_duration = effort,
+                    _ => task.properties.push((key.to_string(), value.to_string())),
+                }
+                continue;
+            }
+
+            // The planning line comes directly below the headline, followed by the property drawer
+            let is_planning = PLANNING_KEYWORDS
+                .iter()
+                .any(|keyword| content.starts_with(keyword));
+            match section {
+                Section::Planning if is_planning => {
+                    apply_planning(task, content).ok_or_else(|| {
+                        eyre!(
+                            "line {}: expected timestamps such as 'DEADLINE: <2025-01-10 Fri>'",
+                            number
+                        )
+                    })?;
+                    section = Section::Drawer;
+                    continue;
+                }
+                Section::Planning | Section::Drawer
+                    if content.eq_ignore_ascii_case(":PROPERTIES:") =>
+                {
+                    section = Section::Properties;
+                    continue;
+                }
+                _ => section = Section::Body,
+            }
+
+            if content.is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            // Take off the spaces that the body was indented with, keeping any other whitespace
+            let indent = task.depth as usize + 2;
+            let leading = line.len() - line.trim_start_matches(' ').len();
+            let line = &line[leading.min(indent)..];
+            if !task.description.is_empty() {
+                task.description.push_str(&"\n".repeat(blank_lines + 1));
+            }
+            task.description.push_str(line.trim_end());
+            blank_lines = 0;
+        }
+
+        // Org only orders the subtasks of a headline when asked to
+        for pos in 0..tasks.len() {
+            let has_subtasks = tasks
+                .get(pos + 1)
+                .is_some_and(|next| next.depth > tasks[pos].depth);
+            if ordered[pos] == Some(false) || (has_subtasks && ordered[pos].is_none()) {
+                tasks[pos].execution_order = ExecutionOrder::Parallel;
+            }
+        }
+
+        let mut task_list = TaskList::new(&name);
+        task_list.tasks = tasks;
+        task_list.rebuild_all_indices();
+        Ok(task_list)
+    }
+}
+
+impl TodoKeywords {
+    /// Parses the keywords of a `#+TODO:` setting such as `TODO DOING | DONE`. The first keyword
+    /// before the `|` is for tasks that are not started and the others for tasks in progress,
+    /// while the keywords after it are for complete tasks. Without a `|`, the last keyword is for
+    /// complete tasks. Returns None if there are no keywords.
+    fn parse(setting: &str) -> Option<TodoKeywords> {
+        // Leave out the fast access keys of keywords, e.g. `TODO(t)`
+        let words = |words: &str| -> Vec<String> {
+            words
+                .split_whitespace()
+                .filter_map(|word| word.split('(').next())
+                .filter(|word| !word.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        let (mut active, complete) = match setting.split_once('|') {
+            Some((active, complete)) => (words(active), words(complete)),
+            None => {
+                let mut active = words(setting);
+                let complete = active.pop().into_iter().collect();
+                (active, complete)
+            }
+        };
+        if active.is_empty() && complete.is_empty() {
+            return None;
+        }
+
+        let not_started = match active.is_empty() {
+            true => vec![],
+            false => vec![active.remove(0)],
+        };
+        Some(TodoKeywords {
+            not_started,
+            in_progress: active,
+            complete,
+        })
+    }
+
+    /// Adds the keywords of another `#+TODO:` setting.
+    fn extend(&mut self, other: TodoKeywords) {
+        self.not_started.extend(other.not_started);
+        self.in_progress.extend(other.in_progress);
+        self.complete.extend(other.complete);
+    }
+
+    /// Gets the status of the tasks marked with a keyword, or None if it is not a keyword.
+    fn status(&self, keyword: &str) -> Option<TaskStatus> {
+        let is = |keywords: &[String]| keywords.iter().any(|k| k == keyword);
+        if is(&self.not_started) {
+            Some(TaskStatus::NotStarted)
+        } else if is(&self.in_progress) {
+            Some(TaskStatus::InProgress(Local::now()))
+        } else if is(&self.complete) {
+            Some(TaskStatus::Complete(Local::now()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Splits a headline such as `** TODO Title` into its level and the text after the stars, or
+/// returns None if the line is not a headline.
+fn parse_headline(line: &str) -> Option<(usize, &str)> {
+    let text = line.trim_start_matches('*');
+    let level = line.len() - text.len();
+    match level > 0 && text.starts_with(' ') {
+        true => Some((level, text.trim())),
+        false => None,
+    }
+}
+
+/// Splits a property such as `:EFFORT: 1:30` into its key and value.
+fn parse_property(content: &str) -> Option<(&str, &str)> {
+    let (key, value) = content.strip_prefix(':')?.split_once(':')?;
+    match key.is_empty() || key.contains(' ') {
+        true => None,
+        false => Some((key, value.trim())),
+    }
+}
+
+/// Parses an effort in hours and minutes such as `1:30` into minutes.
+fn parse_effort(value: &str) -> Option<i32> {
+    let (hours, minutes) = value.split_once(':')?;
+    Some(hours.parse::<i32>().ok()? * 60 + minutes.parse::<i32>().ok()?)
+}
+
+/// Sets the completion, start and due dates of a task from a planning line such as
+/// `CLOSED: [2025-01-06 Mon 10:00] DEADLINE: <2025-01-10 Fri>`. Returns None if the line is not
+/// valid.
+fn apply_planning(task: &mut Task, content: &str) -> Option<()> {
+    let mut rest = content.trim();
+    while !rest.is_empty() {
+        let keyword = PLANNING_KEYWORDS
+            .into_iter()
+            .find(|keyword| rest.starts_with(keyword))?;
+        rest = rest[keyword.len()..].trim_start();
+
+        let close = match rest.chars().next()? {
+            '<' => '>',
+            '[' => ']',
+            _ => return None,
+        };
+        let end = rest.find(close)?;
+        let time = parse_timestamp(&rest[1..end])?;
+        rest = rest[end + 1..].trim_start();
+
+        match keyword {
+            "CLOSED:" => {
+                if let TaskStatus::Complete(_) = task.task_status {
+                    task.task_status = TaskStatus::Complete(time);
+                }
+            }
+            "SCHEDULED:" => task.start_date = Some(time),
+            _ => task.due_date = Some(time),
+        }
+    }
+    Some(())
+}
+
+/// Parses the inside of a timestamp such as `2025-01-10 Fri 10:00`. The weekday and any repeater
+/// are ignored, and a timestamp without a time is at the start of the day.
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Local>> {
+    let mut parts = timestamp.split_whitespace();
+    let date = NaiveDate::parse_from_str(parts.next()?, "%Y-%m-%d").ok()?;
+    let time = parts
+        .find_map(|part| NaiveTime::parse_from_str(part, "%H:%M").ok())
+        .unwrap_or(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&NaiveDateTime::new(date, time))
+        .earliest()
+}
+
+/// Formats an active timestamp, leaving out the time when it is at the start of the day.
+fn format_timestamp(time: &DateTime<Local>) -> String {
+    match time.hour() == 0 && time.minute() == 0 {
+        true => time.format("<%Y-%m-%d %a>").to_string(),
+        false => time.format("<%Y-%m-%d %a %H:%M>").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::{ExecutionOrder, TaskStatus};
+    use crate::task_list::TaskList;
+
+    const ORG: &str = "#+TITLE: Plan
+#+TODO: TODO DOING | DONE
+
+* DOING Launch
+  DEADLINE: <2025-01-10 Fri>
+  :PROPERTIES:
+  :ID: 0b5c1d2e-3f40-4a5b-8c6d-7e8f9a0b1c2d
+  :ORDERED: nil
+  :CATEGORY: work
+  :END:
+  The description of the launch.
+
+  - With a list
+** DONE Write the docs
+   CLOSED: [2025-01-06 Mon 10:30]
+   :PROPERTIES:
+   :ID: 7e1a2b3c-4d5e-4f60-8172-839495a6b7c8
+   :EFFORT: 1:30
+   :END:
+** TODO Build the release
+   SCHEDULED: <2025-01-07 Tue 09:00>
+   :PROPERTIES:
+   :ID: 1a2b3c4d-5e6f-4a7b-8c9d-0e1f2a3b4c5d
+   :ORDERED: t
+   :END:
+*** TODO Compile
+    :PROPERTIES:
+    :ID: 2b3c4d5e-6f7a-4b8c-9d0e-1f2a3b4c5d6e
+    :END:
+";
+
+    #[test]
+    fn org_round_trips() {
+        let task_list = TaskList::from_org(ORG).unwrap();
+        assert_eq!(task_list.name, "Plan");
+        assert_eq!(task_list.len(), 4);
+
+        let launch = &task_list.tasks[0];
+        assert!(matches!(launch.task_status, TaskStatus::InProgress(_)));
+        assert_eq!(launch.execution_order, ExecutionOrder::Parallel);
+        assert_eq!(
+            launch.properties,
+            vec![(String::from("CATEGORY"), String::from("work"))]
+        );
+        assert_eq!(
+            launch.description,
+            "The description of the launch.\n\n- With a list"
+        );
+        assert_eq!(task_list.tasks[1].expected_duration, Some(90));
+        assert_eq!(task_list.tasks[2].execution_order, ExecutionOrder::Series);
+        assert_eq!(task_list.tasks[3].depth, 2);
+
+        assert_eq!(task_list.to_org(), ORG);
+    }
+
+    #[test]
+    fn headlines_use_the_keywords_of_the_outline() {
+        let task_list = TaskList::from_org(
+            "#+TODO: NEXT(n) WAITING | DONE CANCELLED\n\
+             * WAITING Review\n\
+             * CANCELLED Rewrite\n\
+             * NEXT Release\n\
+             * DOING Launch",
+        )
+        .unwrap();
+        let statuses: Vec<&TaskStatus> = task_list
+            .tasks
+            .iter()
+            .map(|task| &task.task_status)
+            .collect();
+        assert!(matches!(statuses[0], TaskStatus::InProgress(_)));
+        assert!(matches!(statuses[1], TaskStatus::Complete(_)));
+        assert_eq!(statuses[2], &TaskStatus::NotStarted);
+        // DOING is only a keyword when the outline declares it
+        assert_eq!(statuses[3], &TaskStatus::NotStarted);
+        assert_eq!(task_list.tasks[3].title, "DOING Launch");
+    }
+
+    #[test]
+    fn malformed_org_gives_line_numbers() {
+        let error = |org: &str| TaskList::from_org(org).unwrap_err().to_string();
+
+        assert_eq!(
+            error("* TODO Launch\n*** TODO Compile"),
+            "line 2: 'TODO Compile' is more than one level below the headline above it"
+        );
+        assert_eq!(
+            error("* TODO Launch\n  DEADLINE: soon"),
+            "line 2: expected timestamps such as 'DEADLINE: <2025-01-10 Fri>'"
+        );
+        assert_eq!(
+            error("* TODO Launch\n  :PROPERTIES:\n  CATEGORY work\n  :END:"),
+            "line 3: expected a property such as ':KEY: value'"
+        );
+        assert_eq!(
+            error("#+TODO: |\n* TODO Launch"),
+            "line 1: expected keywords such as '#+TODO: TODO DOING | DONE'"
+        );
+        assert_eq!(error("* DONE "), "line 1: the headline has no title");
+    }
+
+    #[test]
+    fn parallel_leaves_and_bodies_like_planning_lines_round_trip() {
+        let mut task_list = TaskList::new("Plan");
+        task_list.add_new_root_task_at_end("Launch");
+        task_list.add_new_root_task_at_end("Review");
+        task_list.toggle_execution_order(0);
+        task_list.update_task(0, |task| {
+            task.description = String::from("DEADLINE: when it is ready\n:PROPERTIES:")
+        });
+        task_list.update_task(1, |task| task.description = String::from("CLOSED: never"));
+
+        let imported = TaskList::from_org(&task_list.to_org()).unwrap();
+        assert_eq!(imported.tasks[0].execution_order, ExecutionOrder::Parallel);
+        assert_eq!(imported.tasks[0].due_date, None);
+        assert_eq!(
+            imported.tasks[0].description,
+            "DEADLINE: when it is ready\n:PROPERTIES:"
+        );
+        assert_eq!(imported.tasks[1].execution_order, ExecutionOrder::Series);
+        assert_eq!(imported.tasks[1].description, "CLOSED: never");
+
+        // A planning line below the property drawer is part of the body
+        let task_list =
+            TaskList::from_org("* TODO Launch\n  :PROPERTIES:\n  :END:\n  DEADLINE: soon").unwrap();
+        assert_eq!(task_list.tasks[0].description, "DEADLINE: soon");
+    }
+
+    #[test]
+    fn other_leading_whitespace_is_kept_in_bodies() {
+        let task_list = TaskList::from_org("* TODO Launch\n\u{3000}Notes\n  \tCode").unwrap();
+        assert_eq!(task_list.tasks[0].description, "\u{3000}Notes\n\tCode");
+    }
+}
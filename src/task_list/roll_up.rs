@@ -0,0 +1,261 @@
+use crate::task::TaskStatus;
+use crate::task_list::history::Change;
+use crate::task_list::TaskList;
+use chrono::Local;
+use std::collections::BTreeSet;
+
+/// The rules for changing the status of a parent task when the status of its subtasks changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollUp {
+    /// Start a parent task when any of its subtasks is started.
+    pub start: bool,
+    /// Complete a parent task when all of its subtasks are complete.
+    pub complete: bool,
+    /// Reopen a complete parent task when any of its subtasks is reopened.
+    pub reopen: bool,
+}
+
+impl Default for RollUp {
+    fn default() -> Self {
+        RollUp {
+            start: true,
+            complete: true,
+            reopen: true,
+        }
+    }
+}
+
+impl RollUp {
+    /// Parses a comma separated list of the rules to enable, e.g. `start,complete`. The value
+    /// `none` disables every rule. Returns None if any of the rules is not known.
+    pub fn parse(value: &str) -> Option<RollUp> {
+        let mut roll_up = RollUp {
+            start: false,
+            complete: false,
+            reopen: false,
+        };
+        for rule in value.split(',').map(str::trim) {
+            match rule {
+                "start" => roll_up.start = true,
+                "complete" => roll_up.complete = true,
+                "reopen" => roll_up.reopen = true,
+                "none" | "" => {}
+                _ => return None,
+            }
+        }
+        Some(roll_up)
+    }
+
+    /// Gets the status that a parent task should have given the statuses of its subtasks, or
+    /// None if it should be left as it is.
+    fn parent_status(&self, parent: &TaskStatus, subtasks: &[&TaskStatus]) -> Option<TaskStatus> {
+        let completed: Vec<_> = subtasks
+            .iter()
+            .filter_map(|status| match status {
+                TaskStatus::Complete(time) => Some(*time),
+                _ => None,
+            })
+            .collect();
+        let all_complete = !subtasks.is_empty() && completed.len() == subtasks.len();
+        let started = subtasks
+            .iter()
+            .any(|status| !matches!(status, TaskStatus::NotStarted));
+
+        match parent {
+            TaskStatus::Complete(_) if self.reopen && !all_complete => Some(match started {
+                true => TaskStatus::InProgress(Local::now()),
+                false => TaskStatus::NotStarted,
+            }),
+            TaskStatus::Complete(_) => None,
+            // A parent is complete when its last subtask is
+            _ if self.complete && all_complete => {
+                completed.into_iter().max().map(TaskStatus::Complete)
+            }
+            TaskStatus::NotStarted if self.start && started => {
+                // Use the earliest start of the subtasks that are still in progress
+                let start = subtasks
+                    .iter()
+                    .filter_map(|status| match status {
+                        TaskStatus::InProgress(time) => Some(*time),
+                        _ => None,
+                    })
+                    .min()
+                    .unwrap_or_else(Local::now);
+                Some(TaskStatus::InProgress(start))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TaskList {
+    /// Combines changes to the status of tasks with the changes to their parents required by the
+    /// roll-up rules, so that they are applied and undone as a single change. If the changes would
+    /// complete a parent task by hand while it has subtasks that are not complete, the changes to
+    /// the status of tasks are left out and the notice says why. Returns None if nothing is left.
+    pub(crate) fn roll_up_status(&mut self, changes: Vec<Change>) -> Option<Change> {
+        self.notice = None;
+        let mut statuses: Vec<TaskStatus> = self
+            .tasks
+            .iter()
+            .map(|task| task.task_status.clone())
+            .collect();
+        let mut parents = BTreeSet::new();
+        for change in &changes {
+            if let Change::Status { pos, to, .. } = change {
+                statuses[*pos] = to.clone();
+                let mut child = *pos;
+                while let Some(parent) = self.get_parent(child) {
+                    parents.insert(parent);
+                    child = parent;
+                }
+            }
+        }
+
+        let is_complete = |status: &TaskStatus| matches!(status, TaskStatus::Complete(_));
+        if self.roll_up.complete {
+            let open_parent = changes.iter().find_map(|change| match change {
+                Change::Status { pos, to, .. }
+                    if is_complete(to)
+                        && self
+                            .get_direct_subtasks(*pos)
+                            .into_iter()
+                            .any(|subtask| !is_complete(&statuses[subtask])) =>
+                {
+                    Some(*pos)
+                }
+                _ => None,
+            });
+            if let Some(pos) = open_parent {
+                self.notice = Some(format!(
+                    "'{}' has subtasks that are not complete",
+                    self.tasks[pos].title
+                ));
+                let mut changes: Vec<Change> = changes
+                    .into_iter()
+                    .filter(|change| !matches!(change, Change::Status { .. }))
+                    .collect();
+                return match changes.len() {
+                    0 => None,
+                    1 => Some(changes.remove(0)),
+                    _ => Some(Change::Batch(changes)),
+                };
+            }
+        }
+
+        // Parents are always above their subtasks, so working up the list from the bottom rolls
+        // up every subtask before its parent.
+        let mut changes = changes;
+        for &parent in parents.iter().rev() {
+            let subtasks: Vec<&TaskStatus> = self
+                .get_direct_subtasks(parent)
+                .into_iter()
+                .map(|subtask| &statuses[subtask])
+                .collect();
+            if let Some(to) = self.roll_up.parent_status(&statuses[parent], &subtasks) {
+                changes.push(Change::Status {
+                    pos: parent,
+                    from: statuses[parent].clone(),
+                    to: to.clone(),
+                });
+                statuses[parent] = to;
+            }
+        }
+
+        Some(match changes.len() {
+            1 => changes.remove(0),
+            _ => Change::Batch(changes),
+        })
+    }
+
+    /// Gets the percentage of the leaf tasks below a task that are complete, or None if the task
+    /// has no subtasks.
+    pub(crate) fn percent_complete(&self, pos: usize) -> Option<u8> {
+        if !self.has_subtasks(pos) {
+            return None;
+        }
+
+        let leaves: Vec<usize> = (pos + 1..=self.get_last_subtask_pos(pos))
+            .filter(|&subtask| !self.has_subtasks(subtask))
+            .collect();
+        let complete = leaves
+            .iter()
+            .filter(|&&leaf| matches!(self.tasks[leaf].task_status, TaskStatus::Complete(_)))
+            .count();
+        Some((complete * 100 / leaves.len()) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::TaskStatus;
+    use crate::task_list::roll_up::RollUp;
+
+    #[test]
+    fn status_rolls_up_to_parents() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.add_new_subtask("Task 1.1.1", 1);
+        let status = |task_list: &crate::task_list::TaskList, pos: usize| {
+            task_list.get_task(pos).unwrap().task_status.clone()
+        };
+
+        // Starting a subtask starts every parent above it
+        task_list.start_task(2);
+        assert!(matches!(status(&task_list, 1), TaskStatus::InProgress(_)));
+        assert!(matches!(status(&task_list, 0), TaskStatus::InProgress(_)));
+
+        // Completing every subtask completes the parent at the time of the last subtask
+        task_list.complete_task(2).complete_task(3).complete_task(4);
+        assert_eq!(status(&task_list, 1), status(&task_list, 2));
+        assert!(matches!(status(&task_list, 0), TaskStatus::Complete(_)));
+        assert_eq!(task_list.percent_complete(0), Some(100));
+
+        // Reopening a subtask reopens its parents, and undo reverses the whole roll-up
+        task_list.toggle_task_status(4);
+        assert!(matches!(status(&task_list, 0), TaskStatus::InProgress(_)));
+        assert_eq!(task_list.percent_complete(0), Some(66));
+        task_list.undo();
+        assert!(matches!(status(&task_list, 0), TaskStatus::Complete(_)));
+    }
+
+    #[test]
+    fn roll_up_rules_can_be_disabled() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.roll_up = RollUp::parse("complete").unwrap();
+
+        task_list.start_task(1);
+        assert_eq!(
+            task_list.get_task(0).unwrap().task_status,
+            TaskStatus::NotStarted
+        );
+        assert_eq!(RollUp::parse("none"), RollUp::parse(""));
+        assert_eq!(RollUp::parse("start,finish"), None);
+    }
+
+    #[test]
+    fn open_parents_cannot_be_completed_by_hand() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.start_task(0).complete_task(1).complete_task(0);
+        let task = task_list.get_task(0).unwrap();
+        assert!(matches!(task.task_status, TaskStatus::InProgress(_)));
+        assert!(!task.is_tracking());
+        assert_eq!(
+            task_list.notice.as_deref(),
+            Some("'Task 1' has subtasks that are not complete")
+        );
+
+        // The notice is cleared by the next change to a status
+        task_list.complete_task(2);
+        assert_eq!(task_list.notice, None);
+
+        // A parent can be completed along with the rest of its subtasks
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list
+            .toggle_tasks_status(0, 3)
+            .toggle_tasks_status(0, 3);
+        assert!(matches!(
+            task_list.get_task(0).unwrap().task_status,
+            TaskStatus::Complete(_)
+        ));
+    }
+}
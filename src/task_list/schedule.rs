@@ -0,0 +1,272 @@
+use crate::task::{ExecutionOrder, TaskStatus};
+use crate::task_list::estimates::remaining_work;
+use crate::task_list::TaskList;
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+
+/// The working hours that tasks are scheduled into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkCalendar {
+    /// The time that work starts each working day.
+    pub day_start: NaiveTime,
+    /// The time that work stops each working day.
+    pub day_end: NaiveTime,
+    /// The days of the week that are worked, starting from Monday.
+    pub work_days: [bool; 7],
+}
+
+impl Default for WorkCalendar {
+    fn default() -> Self {
+        WorkCalendar {
+            day_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+            day_end: NaiveTime::from_hms_opt(17, 0, 0).unwrap_or_default(),
+            work_days: [true, true, true, true, true, false, false],
+        }
+    }
+}
+
+impl WorkCalendar {
+    /// Parses working hours and days such as `09:00-17:00 mon-fri` or `08:30-16:30 mon,wed,fri`.
+    /// Returns None if the calendar is not valid or has no working time.
+    pub fn parse(value: &str) -> Option<WorkCalendar> {
+        let (hours, days) = value.trim().split_once(' ')?;
+        let (day_start, day_end) = hours.split_once('-')?;
+        let day_start = NaiveTime::parse_from_str(day_start.trim(), "%H:%M").ok()?;
+        let day_end = NaiveTime::parse_from_str(day_end.trim(), "%H:%M").ok()?;
+
+        let mut work_days = [false; 7];
+        for days in days.split(',') {
+            let (first, last) = days.split_once('-').unwrap_or((days, days));
+            let first = first.trim().parse::<Weekday>().ok()?.num_days_from_monday();
+            let last = last.trim().parse::<Weekday>().ok()?.num_days_from_monday();
+            // Ranges can wrap around the end of the week, e.g. `sun-thu`
+            let mut day = first;
+            loop {
+                work_days[day as usize] = true;
+                if day == last {
+                    break;
+                }
+                day = (day + 1) % 7;
+            }
+        }
+
+        let calendar = WorkCalendar {
+            day_start,
+            day_end,
+            work_days,
+        };
+        calendar.has_working_time().then_some(calendar)
+    }
+
+    /// Returns true if there is any time in the week to work in.
+    fn has_working_time(&self) -> bool {
+        self.day_start < self.day_end && self.work_days.contains(&true)
+    }
+
+    /// Returns true if work is done on the day of the given date and time.
+    fn is_work_day(&self, time: NaiveDateTime) -> bool {
+        self.work_days[time.weekday().num_days_from_monday() as usize]
+    }
+
+    /// Gets the first working time at or after the given time.
+    pub fn next_working_time(&self, time: NaiveDateTime) -> NaiveDateTime {
+        if !self.has_working_time() {
+            return time;
+        }
+
+        let mut time = time;
+        loop {
+            if self.is_work_day(time) && time.time() < self.day_end {
+                return time.max(time.date().and_time(self.day_start));
+            }
+            let Some(next_day) = time.date().checked_add_days(Days::new(1)) else {
+                return time;
+            };
+            time = next_day.and_time(self.day_start);
+        }
+    }
+
+    /// Gets the time at which the given number of working minutes, started at the given time,
+    /// will be finished.
+    pub fn add_working_minutes(&self, start: NaiveDateTime, minutes: i32) -> NaiveDateTime {
+        let mut time = self.next_working_time(start);
+        let mut minutes = TimeDelta::minutes(minutes.max(0) as i64);
+        if !self.has_working_time() {
+            return time + minutes;
+        }
+
+        // Skip whole weeks at once, as every week from any time has the same working time in it.
+        // At least a minute is left over so that work finishing at the end of a day does so then,
+        // rather than at the start of the next working day.
+        let week = (self.day_end - self.day_start)
+            * self.work_days.iter().filter(|&&day| day).count() as i32;
+        let weeks = (minutes - TimeDelta::minutes(1)).num_minutes() / week.num_minutes();
+        if weeks > 0 {
+            let Some(date) = time.date().checked_add_days(Days::new(weeks as u64 * 7)) else {
+                return time;
+            };
+            time = date.and_time(time.time());
+            minutes -= week * weeks as i32;
+        }
+
+        loop {
+            let left_today = time.date().and_time(self.day_end) - time;
+            if minutes <= left_today {
+                return time + minutes;
+            }
+            minutes -= left_today;
+            time = self.next_working_time(time.date().and_time(self.day_end));
+        }
+    }
+}
+
+/// The projected start and finish of a task.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Forecast {
+    /// The earliest time that work on the task can start.
+    pub start: NaiveDateTime,
+    /// The time that all the work on the task is projected to be finished.
+    pub finish: NaiveDateTime,
+    /// True if the task is projected to finish after the day it is due.
+    pub late: bool,
+}
+
+impl TaskList {
+    /// Projects the start and finish of every task that is not complete from the given time,
+    /// fitting the work left on the tasks into the working hours of the calendar. Series subtasks
+    /// follow one another while parallel subtasks, and root tasks, are all started together.
+    pub(crate) fn forecast(&self, now: NaiveDateTime) -> Vec<Option<Forecast>> {
+        let mut forecasts = vec![None; self.len()];
+        for pos in 0..self.len() {
+            if self.tasks[pos].depth == 0 {
+                self.forecast_task(pos, now, &mut forecasts);
+            }
+        }
+        forecasts
+    }
+
+    /// Projects the start and finish of a task and its subtasks when it can start no earlier than
+    /// the given time. Returns the projected finish of the task.
+    fn forecast_task(
+        &self,
+        pos: usize,
+        earliest: NaiveDateTime,
+        forecasts: &mut [Option<Forecast>],
+    ) -> NaiveDateTime {
+        let Ok(task) = self.get_task(pos) else {
+            return earliest;
+        };
+        // Complete tasks take up no more time
+        if let TaskStatus::Complete(_) = task.task_status {
+            return earliest;
+        }
+
+        let start_date = task.start_date.map(|date| date.naive_local());
+        let start = self
+            .calendar
+            .next_working_time(start_date.map_or(earliest, |date| date.max(earliest)));
+
+        let subtasks = self.get_direct_subtasks(pos);
+        let finish = match (subtasks.is_empty(), &task.execution_order) {
+            (true, _) => self
+                .calendar
+                .add_working_minutes(start, remaining_work(task)),
+            (false, ExecutionOrder::Series) => subtasks.iter().fold(start, |time, &subtask| {
+                self.forecast_task(subtask, time, forecasts)
+            }),
+            (false, ExecutionOrder::Parallel) => subtasks
+                .iter()
+                .map(|&subtask| self.forecast_task(subtask, start, forecasts))
+                .max()
+                .unwrap_or(start),
+        };
+
+        let late = task
+            .due_date
+            .is_some_and(|due_date| finish.date() > due_date.date_naive());
+        forecasts[pos] = Some(Forecast {
+            start,
+            finish,
+            late,
+        });
+        finish
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task_list::schedule::WorkCalendar;
+    use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2024-01-01 is a Monday
+        NaiveDate::from_ymd_opt(2024, 1, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .unwrap()
+    }
+
+    #[test]
+    fn working_minutes_skip_nights_and_weekends() {
+        let calendar = WorkCalendar::default();
+        assert_eq!(calendar.add_working_minutes(at(1, 10, 0), 60), at(1, 11, 0));
+        assert_eq!(
+            calendar.add_working_minutes(at(1, 16, 0), 120),
+            at(2, 10, 0)
+        );
+        // Friday afternoon carries over to Monday
+        assert_eq!(
+            calendar.add_working_minutes(at(5, 16, 30), 60),
+            at(8, 9, 30)
+        );
+        // Work started outside of working hours starts at the next working time
+        assert_eq!(calendar.add_working_minutes(at(6, 12, 0), 30), at(8, 9, 30));
+        // Whole weeks of work finish at the end of a working day
+        assert_eq!(
+            calendar.add_working_minutes(at(1, 9, 0), 2 * 40 * 60),
+            at(12, 17, 0)
+        );
+        assert_eq!(
+            calendar.add_working_minutes(at(3, 13, 0), 40 * 60 + 30),
+            at(10, 13, 30)
+        );
+        assert!(calendar.add_working_minutes(at(1, 9, 0), i32::MAX) > at(1, 9, 0));
+    }
+
+    #[test]
+    fn calendar_is_parsed() {
+        let calendar = WorkCalendar::parse("08:30-16:30 sun-tue,thu").unwrap();
+        assert_eq!(calendar.day_start.to_string(), "08:30:00");
+        assert_eq!(
+            calendar.work_days,
+            [true, true, false, true, false, false, true]
+        );
+        assert_eq!(WorkCalendar::parse("17:00-09:00 mon-fri"), None);
+        assert_eq!(WorkCalendar::parse("09:00-17:00"), None);
+    }
+
+    #[test]
+    fn series_tasks_follow_one_another() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        for pos in [1, 2, 3] {
+            task_list.update_task(pos, |task| task.expected_duration = Some(240));
+        }
+        task_list.update_task(0, |task| {
+            task.due_date = Local.from_local_datetime(&at(1, 0, 0)).earliest()
+        });
+
+        let forecasts = task_list.forecast(at(1, 9, 0));
+        let finish = |pos: usize| forecasts[pos].map(|forecast| forecast.finish);
+        assert_eq!(finish(1), Some(at(1, 13, 0)));
+        assert_eq!(finish(2), Some(at(1, 17, 0)));
+        assert_eq!(finish(3), Some(at(2, 13, 0)));
+        assert_eq!(finish(0), Some(at(2, 13, 0)));
+        assert!(forecasts[0].is_some_and(|forecast| forecast.late));
+
+        task_list.toggle_execution_order(0);
+        let forecasts = task_list.forecast(at(1, 9, 0));
+        assert_eq!(
+            forecasts[0].map(|forecast| forecast.finish),
+            Some(at(1, 13, 0))
+        );
+        assert!(forecasts[0].is_some_and(|forecast| !forecast.late));
+    }
+}
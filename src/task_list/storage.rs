@@ -0,0 +1,240 @@
+use crate::task::Task;
+use crate::task_list::TaskList;
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The current version of the task list file format. Increment this whenever the shape of a
+/// stored `Task` changes, and add a matching step to `migrate`.
+pub(crate) const SCHEMA_VERSION: u32 = 2;
+
+/// The on-disk representation of a task list. Only the tasks themselves are stored, the indices
+/// are rebuilt after loading.
+#[derive(Serialize, Deserialize)]
+struct TaskListFile {
+    /// The version of the schema that the file was written with.
+    version: u32,
+    /// The name of the task list.
+    name: String,
+    /// The tasks in the list, in order.
+    tasks: Vec<Task>,
+}
+
+impl TaskList {
+    /// Serialises the task list to a versioned JSON string.
+    pub(crate) fn to_json(&self) -> Result<String> {
+        let file = TaskListFile {
+            version: SCHEMA_VERSION,
+            name: self.name.clone(),
+            tasks: self.tasks.clone(),
+        };
+        Ok(serde_json::to_string_pretty(&file)?)
+    }
+
+    /// Deserialises a task list from a versioned JSON string, migrating older versions and
+    /// rebuilding all the indices.
+    pub(crate) fn from_json(json: &str) -> Result<TaskList> {
+        let value = migrate(serde_json::from_str(json)?)?;
+        let file: TaskListFile = serde_json::from_value(value)?;
+
+        let mut task_list = TaskList::new(&file.name);
+        task_list.tasks = file.tasks;
+        task_list.rebuild_all_indices();
+        Ok(task_list)
+    }
+
+    /// Saves the task list to a JSON file at the given path, keeping up to `backups` previous
+    /// versions of the file alongside it.
+    ///
+    /// The list is written to a temporary file first and then renamed over the existing file, so
+    /// an interrupted save leaves either the old or the new list on disk, never a partial one.
+    pub fn save(&self, path: &Path, backups: usize) -> Result<()> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+
+        let temp_path = temp_path(path);
+        let mut file = File::create(&temp_path)?;
+        file.write_all(self.to_json()?.as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+
+        if path.exists() {
+            rotate_backups(path, backups)?;
+        }
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Loads a task list from a JSON file at the given path.
+    pub fn load(path: &Path) -> Result<TaskList> {
+        let json = std::fs::read_to_string(path)?;
+        TaskList::from_json(&json)
+    }
+}
+
+/// Gets the path of the temporary file used while saving to `path`.
+fn temp_path(path: &Path) -> PathBuf {
+    append_extension(path, "tmp")
+}
+
+/// Gets the path of the `n`th most recent backup of the file at `path`.
+fn backup_path(path: &Path, n: usize) -> PathBuf {
+    append_extension(path, &n.to_string())
+}
+
+/// Appends an extra extension to a path, e.g. `tasks.json` becomes `tasks.json.1`.
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
+
+/// Shifts each backup of the file at `path` along by one, dropping the oldest, and copies the
+/// current file into the most recent backup slot. The current file is copied rather than moved so
+/// that it stays in place until the new version is renamed over it.
+fn rotate_backups(path: &Path, backups: usize) -> Result<()> {
+    if backups == 0 {
+        return Ok(());
+    }
+
+    let oldest = backup_path(path, backups);
+    if oldest.exists() {
+        std::fs::remove_file(oldest)?;
+    }
+    for n in (1..backups).rev() {
+        let backup = backup_path(path, n);
+        if backup.exists() {
+            std::fs::rename(backup, backup_path(path, n + 1))?;
+        }
+    }
+    std::fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// Brings a stored task list up to the current schema version, one version at a time.
+fn migrate(value: Value) -> Result<Value> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| eyre!("Task list file has no schema version"))?;
+
+    match version {
+        v if v == SCHEMA_VERSION as u64 => Ok(value),
+        1 => migrate(migrate_v1(value)),
+        v if v > SCHEMA_VERSION as u64 => Err(eyre!(
+            "Task list file version {} is newer than the supported version {}",
+            v,
+            SCHEMA_VERSION
+        )),
+        v => Err(eyre!("No migration exists for task list file version {}", v)),
+    }
+}
+
+/// Migrates a version 1 task list, whose tasks have no fold state, work sessions or kept
+/// properties, to version 2.
+fn migrate_v1(mut value: Value) -> Value {
+    value["version"] = Value::from(2);
+    if let Some(tasks) = value.get_mut("tasks").and_then(Value::as_array_mut) {
+        for task in tasks.iter_mut().filter_map(Value::as_object_mut) {
+            task.entry("folded").or_insert(Value::Bool(false));
+            task.entry("sessions").or_insert(Value::Array(vec![]));
+            task.entry("properties").or_insert(Value::Array(vec![]));
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backup_path, temp_path};
+    use crate::task::{ExecutionOrder, TaskStatus};
+    use crate::task_list::TaskList;
+
+    #[test]
+    fn round_trip_preserves_tasks_and_rebuilds_indices() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.toggle_execution_order(4);
+        task_list.toggle_task_status(1);
+        task_list.get_mut_task(2).unwrap().expected_duration = Some(30);
+
+        let loaded = TaskList::from_json(&task_list.to_json().unwrap()).unwrap();
+
+        assert_eq!(loaded.name, "Task List");
+        assert_eq!(loaded.print_debug(), task_list.print_debug());
+        assert_eq!(loaded.tasks, task_list.tasks);
+        assert_eq!(loaded.depth_index, task_list.depth_index);
+        assert_eq!(loaded.title_index, task_list.title_index);
+        assert_eq!(loaded.next_tasks, task_list.next_tasks);
+        assert_eq!(
+            loaded.get_task(4).unwrap().execution_order,
+            ExecutionOrder::Parallel
+        );
+        assert!(matches!(
+            loaded.get_task(1).unwrap().task_status,
+            TaskStatus::InProgress(_)
+        ));
+        assert_eq!(loaded.get_task(2).unwrap().expected_duration, Some(30));
+    }
+
+    #[test]
+    fn save_keeps_rotating_backups() {
+        let directory = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let path = directory.join("tasks.json");
+        let mut task_list = TaskList::new("Task List");
+
+        for i in 0..4 {
+            task_list.add_new_root_task_at_end(&format!("Task {}", i));
+            task_list.save(&path, 2).unwrap();
+        }
+
+        assert_eq!(TaskList::load(&path).unwrap().len(), 4);
+        assert_eq!(TaskList::load(&backup_path(&path, 1)).unwrap().len(), 3);
+        assert_eq!(TaskList::load(&backup_path(&path, 2)).unwrap().len(), 2);
+        assert!(!backup_path(&path, 3).exists());
+        assert!(!temp_path(&path).exists());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn version_1_files_are_migrated() {
+        let json = r#"{ "version": 1, "name": "Task List", "tasks": [{
+            "id": "0b5c1d2e-3f40-4a5b-8c6d-7e8f9a0b1c2d",
+            "title": "Task 1",
+            "description": "",
+            "is_next": false,
+            "is_critical": false,
+            "task_status": "NotStarted",
+            "execution_order": "Series",
+            "depth": 0,
+            "creation_date": "2025-01-02T09:00:00+00:00",
+            "start_date": null,
+            "due_date": null,
+            "expected_duration": null
+        }] }"#;
+        let task_list = TaskList::from_json(json).unwrap();
+        let task = task_list.get_task(0).unwrap();
+        assert_eq!(task.title, "Task 1");
+        assert!(!task.folded);
+        assert!(task.sessions.is_empty());
+        assert!(task.properties.is_empty());
+    }
+
+    #[test]
+    fn newer_schema_version_is_rejected() {
+        let json = r#"{ "version": 999, "name": "Task List", "tasks": [] }"#;
+        assert!(TaskList::from_json(json).is_err());
+    }
+
+    #[test]
+    fn missing_schema_version_is_rejected() {
+        let json = r#"{ "name": "Task List", "tasks": [] }"#;
+        assert!(TaskList::from_json(json).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use crate::task::TaskStatus;
+use crate::task_list::TaskList;
+use chrono::{DateTime, Local, NaiveDate};
+
+impl TaskList {
+    /// Gets the positions of the tasks to work on during a day, in list order so that the tasks
+    /// of each root task are together. These are the actionable tasks, tasks in progress, tasks that
+    /// start on the day, and tasks due on or before the day. Tasks completed on the day are kept
+    /// so that they don't disappear as soon as they are done.
+    pub(crate) fn today_tasks(&self, today: NaiveDate) -> Vec<usize> {
+        let on_day = |date: &DateTime<Local>| date.date_naive() == today;
+
+        (0..self.len())
+            .filter(|&pos| {
+                let task = &self.tasks[pos];
+                match task.task_status {
+                    TaskStatus::Complete(time) => on_day(&time),
+                    TaskStatus::InProgress(_) => true,
+                    TaskStatus::NotStarted => {
+                        self.is_actionable(pos)
+                            || task.start_date.as_ref().is_some_and(on_day)
+                            || task.due_date.is_some_and(|date| date.date_naive() <= today)
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Local};
+
+    #[test]
+    fn today_tasks_include_actionable_and_due_tasks() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let today = Local::now().date_naive();
+        let actionable: Vec<usize> = (0..task_list.len())
+            .filter(|&pos| task_list.is_actionable(pos))
+            .collect();
+        assert_eq!(actionable, vec![1, 5, 8]);
+
+        // Task 2.2 is due tomorrow, while Task 1.2 starts today and Task 2.3 is overdue even
+        // though neither of them is actionable
+        task_list.update_task(6, |task| {
+            task.due_date = Some(Local::now() + Duration::days(1))
+        });
+        task_list.update_task(2, |task| task.start_date = Some(Local::now()));
+        task_list.update_task(7, |task| {
+            task.due_date = Some(Local::now() - Duration::days(1))
+        });
+        assert!(!task_list.is_actionable(2) && !task_list.is_actionable(7));
+
+        assert_eq!(task_list.today_tasks(today), vec![1, 2, 5, 7, 8]);
+
+        // Tasks completed today are still listed
+        task_list.complete_task(8);
+        assert!(task_list.today_tasks(today).contains(&8));
+    }
+}
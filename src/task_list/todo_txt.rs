@@ -0,0 +1,339 @@
+use crate::task::{Task, TaskStatus};
+use crate::task_list::history::Change;
+use crate::task_list::TaskList;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use color_eyre::eyre::{eyre, Result};
+use std::iter::Peekable;
+use std::str::SplitWhitespace;
+use uuid::Uuid;
+
+/// The format of the dates in a todo.txt file.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A task read from a line of a todo.txt file.
+struct TodoLine {
+    /// The id of the task that the line was exported from, if it has one.
+    id: Option<Uuid>,
+    title: String,
+    /// The path of the parent of the task, as written by `task_path`.
+    project: Option<String>,
+    complete: bool,
+    completion_date: Option<NaiveDate>,
+    creation_date: Option<NaiveDate>,
+    due_date: Option<NaiveDate>,
+}
+
+impl TaskList {
+    /// Writes every task in the list as a line of a todo.txt file. The parents of a task are
+    /// written as a `+project` tag, and the id of the task as an `id:` tag so that the lines can be
+    /// merged back into the list.
+    pub(crate) fn to_todo_txt(&self) -> String {
+        let mut todo_txt = String::new();
+        for (pos, task) in self.tasks.iter().enumerate() {
+            let mut words = vec![];
+            if let TaskStatus::Complete(time) = task.task_status {
+                words.push(String::from("x"));
+                words.push(time.format(DATE_FORMAT).to_string());
+            }
+            words.push(task.creation_date.format(DATE_FORMAT).to_string());
+            words.extend(task.title.split_whitespace().map(escape_word));
+            if let Some(parent) = self.get_parent(pos) {
+                words.push(format!("+{}", self.task_path(parent)));
+            }
+            if let Some(due_date) = task.due_date {
+                words.push(format!("due:{}", due_date.format(DATE_FORMAT)));
+            }
+            words.push(format!("id:{}", task.id));
+
+            todo_txt.push_str(&words.join(" "));
+            todo_txt.push('\n');
+        }
+        todo_txt
+    }
+
+    /// Merges the lines of a todo.txt file into the list. Lines with the id of a task in the list
+    /// update that task, while other lines are added as new tasks below the task named by their
+    /// project, or as new root tasks. Nothing is merged if any line is not valid, and the whole
+    /// merge is undone as a single change.
+    pub(crate) fn merge_todo_txt(&mut self, todo_txt: &str) -> Result<()> {
+        let lines = todo_txt
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| parse_line(line, index + 1))
+            .collect::<Result<Vec<TodoLine>>>()?;
+
+        // Each line is merged into a copy of the list, as where a new task goes depends on the
+        // tasks added by the lines before it
+        let mut merged = TaskList::new(&self.name);
+        merged.tasks = self.tasks.clone();
+        merged.rebuild_all_indices();
+        let mut changes = vec![];
+        for line in lines {
+            let existing = line
+                .id
+                .and_then(|id| merged.tasks.iter().position(|task| task.id == id));
+            if let Some(pos) = existing {
+                let task = &merged.tasks[pos];
+                let mut updated = task.clone();
+                if line.apply(&mut updated) {
+                    let change = Change::Update {
+                        pos,
+                        from: Box::new(task.clone()),
+                        to: Box::new(updated),
+                    };
+                    merged.execute(change.clone());
+                    changes.push(change);
+                }
+                continue;
+            }
+
+            let parent = line.project.as_ref().and_then(|project| {
+                (0..merged.len()).find(|&pos| &merged.task_path(pos) == project)
+            });
+            let (pos, depth) = match parent {
+                Some(parent) => (
+                    merged.get_last_subtask_pos(parent) + 1,
+                    merged.tasks[parent].depth + 1,
+                ),
+                None => (merged.len(), 0),
+            };
+
+            let mut task = Task::new(&line.title, depth);
+            // Keep the id so that importing the same file again updates the task
+            if let Some(id) = line.id {
+                task.id = id;
+            }
+            if let Some(creation_date) = line.creation_date.and_then(start_of_day) {
+                task.creation_date = creation_date;
+            }
+            line.apply(&mut task);
+            let change = Change::Insert {
+                pos,
+                tasks: vec![task],
+            };
+            merged.execute(change.clone());
+            changes.push(change);
+        }
+
+        if !changes.is_empty() {
+            self.execute(Change::Batch(changes));
+        }
+        Ok(())
+    }
+
+    /// Gets the path of a task as a todo.txt project name, made of the titles of the task and its
+    /// parents joined by `/` with the spaces in each title replaced by `_`.
+    fn task_path(&self, pos: usize) -> String {
+        let mut titles = vec![];
+        let mut current = Some(pos);
+        while let Some(pos) = current {
+            titles.push(
+                self.tasks[pos]
+                    .title
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join("_"),
+            );
+            current = self.get_parent(pos);
+        }
+        titles.reverse();
+        titles.join("/")
+    }
+}
+
+impl TodoLine {
+    /// Updates the title, status and due date of a task to match the line. Returns true if
+    /// anything about the task was changed.
+    fn apply(&self, task: &mut Task) -> bool {
+        let status = match (&task.task_status, self.complete) {
+            (TaskStatus::Complete(_), false) => TaskStatus::NotStarted,
+            (TaskStatus::Complete(_), true) | (_, false) => task.task_status.clone(),
+            (_, true) => TaskStatus::Complete(
+                self.completion_date
+                    .and_then(start_of_day)
+                    .unwrap_or_else(Local::now),
+            ),
+        };
+        let due_date = match (task.due_date, self.due_date) {
+            // Keep the time of a due date that is on the same day
+            (Some(current), Some(date)) if current.date_naive() == date => Some(current),
+            (_, date) => date.and_then(start_of_day),
+        };
+
+        let changed =
+            task.title != self.title || task.task_status != status || task.due_date != due_date;
+        task.title = self.title.clone();
+        task.task_status = status;
+        task.due_date = due_date;
+        changed
+    }
+}
+
+/// Parses a line of a todo.txt file, returning an error with the line number if it is not valid.
+fn parse_line(line: &str, number: usize) -> Result<TodoLine> {
+    let mut words = line.split_whitespace().peekable();
+    let complete = words.next_if_eq(&"x").is_some();
+    let completion_date = match complete {
+        true => take_date(&mut words),
+        false => None,
+    };
+    // Priorities aren't kept, but are skipped so that the creation date can be read
+    words.next_if(|word| word.len() == 3 && word.starts_with('(') && word.ends_with(')'));
+    let creation_date = take_date(&mut words);
+
+    let mut todo_line = TodoLine {
+        id: None,
+        title: String::new(),
+        project: None,
+        complete,
+        completion_date,
+        creation_date,
+        due_date: None,
+    };
+    let mut title = vec![];
+    for word in words {
+        if let Some(word) = word.strip_prefix('\\') {
+            title.push(word);
+        } else if let Some(due_date) = word.strip_prefix("due:") {
+            let due_date = NaiveDate::parse_from_str(due_date, DATE_FORMAT).map_err(|_| {
+                eyre!(
+                    "line {}: '{}' is not a due date in the form YYYY-MM-DD",
+                    number,
+                    due_date
+                )
+            })?;
+            todo_line.due_date = Some(due_date);
+        } else if let Some(id) = word.strip_prefix("id:") {
+            let id = Uuid::parse_str(id)
+                .map_err(|_| eyre!("line {}: '{}' is not a task id", number, id))?;
+            todo_line.id = Some(id);
+        } else if let Some(project) = word
+            .strip_prefix('+')
+            .filter(|project| !project.is_empty() && todo_line.project.is_none())
+        {
+            todo_line.project = Some(project.to_string());
+        } else {
+            title.push(word);
+        }
+    }
+
+    if title.is_empty() {
+        return Err(eyre!("line {}: the task has no title", number));
+    }
+    todo_line.title = title.join(" ");
+    Ok(todo_line)
+}
+
+/// Escapes a word of a title with a backslash if it would otherwise be read as a tag, or if it
+/// starts with a backslash itself.
+fn escape_word(word: &str) -> String {
+    let is_tag = word.starts_with("due:")
+        || word.starts_with("id:")
+        || (word.starts_with('+') && word.len() > 1);
+    match is_tag || word.starts_with('\\') {
+        true => format!("\\{}", word),
+        false => word.to_string(),
+    }
+}
+
+/// Takes the next word if it is a date.
+fn take_date(words: &mut Peekable<SplitWhitespace>) -> Option<NaiveDate> {
+    words
+        .next_if(|word| NaiveDate::parse_from_str(word, DATE_FORMAT).is_ok())
+        .and_then(|word| NaiveDate::parse_from_str(word, DATE_FORMAT).ok())
+}
+
+/// Gets the start of a day in the local time zone.
+fn start_of_day(date: NaiveDate) -> Option<DateTime<Local>> {
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::TaskStatus;
+
+    #[test]
+    fn todo_txt_lines_carry_projects_and_ids() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        task_list.set_task_title(0, "Launch day");
+        task_list.complete_task(1);
+
+        let todo_txt = task_list.to_todo_txt();
+        let line = todo_txt.lines().nth(1).unwrap();
+        let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+        assert_eq!(
+            line,
+            format!(
+                "x {} {} Task 1.1 +Launch_day id:{}",
+                today, today, task_list.tasks[1].id
+            )
+        );
+    }
+
+    #[test]
+    fn todo_txt_is_merged_back_by_id() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let mut todo_txt: String = task_list
+            .to_todo_txt()
+            .lines()
+            .map(|line| match line {
+                line if line.contains("Task 2.1") => format!("x {}\n", line),
+                line => format!(
+                    "{}\n",
+                    line.replace("Task 1.2", "Task 1.2 renamed due:2030-01-02")
+                ),
+            })
+            .collect();
+        todo_txt.push_str("Task 1.4 +Task_1\nTask 4\n");
+
+        task_list.merge_todo_txt(&todo_txt).unwrap();
+        assert_eq!(task_list.len(), 11);
+        assert_eq!(task_list.tasks[2].title, "Task 1.2 renamed");
+        assert!(task_list.tasks[2].due_date.is_some());
+        assert!(matches!(
+            task_list.tasks[6].task_status,
+            TaskStatus::Complete(_)
+        ));
+        // New tasks are added at the end of their project, or as root tasks
+        assert_eq!(task_list.tasks[4].title, "Task 1.4");
+        assert_eq!(task_list.tasks[4].depth, 1);
+        assert_eq!(task_list.tasks[10].title, "Task 4");
+
+        // The whole merge is undone at once
+        task_list.undo();
+        assert_eq!(task_list.len(), 9);
+        assert_eq!(task_list.tasks[2].title, "Task 1.2");
+        assert_eq!(task_list.tasks[5].task_status, TaskStatus::NotStarted);
+        task_list.redo();
+        assert_eq!(task_list.len(), 11);
+
+        // Nothing is merged from a file with an invalid line
+        let error = task_list
+            .merge_todo_txt("Task 5\nTask 6 due:soon")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: 'soon' is not a due date in the form YYYY-MM-DD"
+        );
+        assert_eq!(task_list.len(), 11);
+    }
+
+    #[test]
+    fn titles_that_look_like_tags_round_trip() {
+        let mut task_list = crate::task_list::tests::setup_task_list();
+        let title = "Fix due:date parsing +build \\path";
+        task_list.set_task_title(1, title);
+
+        let todo_txt = task_list.to_todo_txt();
+        assert!(todo_txt.contains(" Fix \\due:date parsing \\+build \\\\path +Task_1 "));
+
+        task_list.set_task_title(1, "Task 1.1");
+        task_list.merge_todo_txt(&todo_txt).unwrap();
+        assert_eq!(task_list.len(), 9);
+        assert_eq!(task_list.tasks[1].title, title);
+        assert_eq!(task_list.tasks[1].depth, 1);
+    }
+}
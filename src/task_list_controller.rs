@@ -1,4 +1,5 @@
-﻿mod events;
+﻿mod detail;
+mod events;
 mod render;
 pub(crate) mod state;
 
@@ -14,6 +15,24 @@ pub(crate) struct TaskListController {
 }
 
 impl TaskListController {
+    /// Creates a controller for an existing task list.
+    pub(crate) fn new(task_list: TaskList) -> TaskListController {
+        TaskListController {
+            task_list,
+            state: TaskListState::default(),
+        }
+    }
+
+    /// Checks whether key presses are currently being used to enter text, in which case they
+    /// should not be treated as commands.
+    pub(crate) fn is_capturing_input(&self) -> bool {
+        match &self.state.mode {
+            TaskListMode::New(_) | TaskListMode::Edit(_) => true,
+            TaskListMode::Detail(state) => state.input.is_some() || state.editor.is_some(),
+            TaskListMode::Normal | TaskListMode::Selection(_) => false,
+        }
+    }
+
     /// Get the currently selected task, if there is one.
     pub(crate) fn selected_task(&self) -> Option<&Task> {
         match self.state.mode {
@@ -0,0 +1,527 @@
+use crate::task::{ExecutionOrder, Task, TaskStatus};
+use crate::task_list_controller::state::{DetailField, TaskDetailState, TaskListMode};
+use crate::task_list_controller::TaskListController;
+use crate::ui::format::{
+    format_date, format_date_time, format_duration, format_relative_date, parse_date,
+    parse_duration,
+};
+use crate::ui::input::{render_input, to_input_request};
+use crate::ui::markdown::markdown_lines;
+use crate::ui::text_area::TextArea;
+use chrono::{DateTime, Local, TimeZone};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph, Widget, Wrap},
+};
+use tui_input::Input;
+
+/// The longest estimate that can be entered in minutes, so that rolled up estimates stay in range.
+const MAX_ESTIMATE: i32 = 1000 * 60;
+
+impl TaskListController {
+    /// Gives the detail pane focus so that the fields of the selected task can be edited.
+    pub(crate) fn start_detail_mode(&mut self) {
+        if self.selected_task().is_none() {
+            return;
+        }
+        self.state.mode = TaskListMode::Detail(TaskDetailState::default());
+    }
+
+    /// Gives the detail pane focus with the description of the selected task open for editing.
+    pub(crate) fn start_description_editor(&mut self) {
+        let Some(task) = self.selected_task() else {
+            return;
+        };
+        let editor = TextArea::new(&task.description);
+        self.state.mode = TaskListMode::Detail(TaskDetailState {
+            editor: Some(editor),
+            ..TaskDetailState::default()
+        });
+    }
+
+    /// Gives the detail pane focus with the estimate of the selected task open for editing.
+    pub(crate) fn start_estimate_editor(&mut self) {
+        if self.selected_task().is_none() {
+            return;
+        }
+        self.state.mode = TaskListMode::Detail(TaskDetailState {
+            field: DetailField::ExpectedDuration,
+            ..TaskDetailState::default()
+        });
+        self.edit_detail_field();
+    }
+
+    /// Checks whether the detail pane currently has focus.
+    pub(crate) fn is_detail_focused(&self) -> bool {
+        matches!(self.state.mode, TaskListMode::Detail(_))
+    }
+
+    /// Handles a key press while the detail pane has focus.
+    pub(crate) fn handle_detail_key_event(&mut self, key_event: KeyEvent) {
+        let TaskListMode::Detail(state) = &mut self.state.mode else {
+            return;
+        };
+
+        // Pass keys through to the editor while the description is being edited
+        if let Some(editor) = &mut state.editor {
+            match key_event.code {
+                KeyCode::Esc => {
+                    let description = editor.text();
+                    state.editor = None;
+                    let pos = self.state.selected_pos;
+                    self.task_list
+                        .update_task(pos, |task| task.description = description);
+                }
+                KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    state.editor = None;
+                }
+                _ => editor.handle_key(&key_event),
+            }
+            return;
+        }
+
+        // Pass keys through to the input while a field is being edited
+        if let Some(input) = &mut state.input {
+            match key_event.code {
+                KeyCode::Enter => self.commit_detail_field(),
+                KeyCode::Esc => {
+                    state.input = None;
+                    state.error = None;
+                }
+                _ => {
+                    if let Some(request) = to_input_request(&key_event) {
+                        input.handle(request);
+                    }
+                }
+            }
+            return;
+        }
+
+        let index = DetailField::ALL
+            .iter()
+            .position(|&field| field == state.field)
+            .unwrap_or(0);
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                state.field = DetailField::ALL[(index + 1).min(DetailField::ALL.len() - 1)];
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                state.field = DetailField::ALL[index.saturating_sub(1)];
+            }
+            KeyCode::Char('J') => state.description_scroll += 1,
+            KeyCode::Char('K') => {
+                state.description_scroll = state.description_scroll.saturating_sub(1);
+            }
+            KeyCode::Enter => self.edit_detail_field(),
+            KeyCode::Esc | KeyCode::Char('e') => self.state.mode = TaskListMode::Normal,
+            _ => {}
+        }
+    }
+
+    /// Starts editing the selected field, or changes it directly if it has a fixed set of values.
+    fn edit_detail_field(&mut self) {
+        let pos = self.state.selected_pos;
+        let TaskListMode::Detail(state) = &self.state.mode else {
+            return;
+        };
+        let field = state.field;
+
+        match field {
+            DetailField::Status => {
+                self.task_list.toggle_task_status(pos);
+                return;
+            }
+            DetailField::ExecutionOrder => {
+                self.task_list.toggle_execution_order(pos);
+                return;
+            }
+            DetailField::Description => {
+                self.start_description_editor();
+                return;
+            }
+            _ => {}
+        }
+
+        let Some(task) = self.selected_task() else {
+            return;
+        };
+        let value = match field {
+            DetailField::StartDate => task.start_date.as_ref().map(format_date),
+            DetailField::DueDate => task.due_date.as_ref().map(format_date),
+            DetailField::ExpectedDuration => task.expected_duration.map(format_duration),
+            DetailField::Description | DetailField::Status | DetailField::ExecutionOrder => None,
+        }
+        .unwrap_or_default();
+
+        if let TaskListMode::Detail(state) = &mut self.state.mode {
+            state.input = Some(Input::default().with_value(value));
+        }
+    }
+
+    /// Parses the edited value of the selected field and saves it to the task. The input is left
+    /// open with an error if the value is not valid.
+    fn commit_detail_field(&mut self) {
+        let pos = self.state.selected_pos;
+        let TaskListMode::Detail(state) = &self.state.mode else {
+            return;
+        };
+        let Some(input) = &state.input else {
+            return;
+        };
+        let field = state.field;
+        let value = input.value().trim().to_string();
+
+        let task_list = &mut self.task_list;
+        let result = match field {
+            DetailField::StartDate => parse_task_date(&value).map(|date| {
+                task_list.update_task(pos, |task| task.start_date = date);
+            }),
+            DetailField::DueDate => parse_task_date(&value).map(|date| {
+                task_list.update_task(pos, |task| task.due_date = date);
+            }),
+            DetailField::ExpectedDuration => parse_estimate(&value).map(|minutes| {
+                task_list.update_task(pos, |task| task.expected_duration = minutes);
+            }),
+            DetailField::Description | DetailField::Status | DetailField::ExecutionOrder => Ok(()),
+        };
+
+        let TaskListMode::Detail(state) = &mut self.state.mode else {
+            return;
+        };
+        match result {
+            Ok(()) => {
+                state.input = None;
+                state.error = None;
+            }
+            Err(error) => state.error = Some(error),
+        }
+    }
+
+    /// Renders the details of the selected task into the given area.
+    pub(crate) fn render_detail(&mut self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .title(" Details ".bold());
+        let block = match self.is_detail_focused() {
+            true => block.blue(),
+            false => block,
+        };
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        let Some(task) = self.selected_task() else {
+            Paragraph::new("No task selected".dark_gray()).render(inner_area, buf);
+            return;
+        };
+        let state = match &self.state.mode {
+            TaskListMode::Detail(state) => Some(state),
+            _ => None,
+        };
+        let pos = self.state.selected_pos;
+
+        let mut lines = vec![Line::from(task.title.clone()).bold(), Line::from("")];
+        let mut input_row = None;
+        for field in DetailField::ALL {
+            let selected = state.is_some_and(|state| state.field == field);
+            let label = format!("{:<11}", field_label(field));
+            let label = match selected {
+                true => label.reversed(),
+                false => label.bold(),
+            };
+            if selected && state.is_some_and(|state| state.input.is_some()) {
+                input_row = Some(lines.len());
+            }
+            lines.push(Line::from(vec![
+                label,
+                " ".into(),
+                field_value(task, field).into(),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            format!("{:<11}", "Created").bold(),
+            " ".into(),
+            format_date_time(&task.creation_date).into(),
+        ]));
+        lines.push(Line::from(vec![
+            format!("{:<11}", "Total").bold(),
+            " ".into(),
+            format_duration(self.task_list.calculate_task_duration(pos)).into(),
+        ]));
+        lines.push(Line::from(vec![
+            format!("{:<11}", "Actual").bold(),
+            " ".into(),
+            format_duration(self.task_list.actual_time(pos).num_minutes() as i32).into(),
+        ]));
+        if let Some(completion_time) = task.completion_time() {
+            lines.push(Line::from(vec![
+                format!("{:<11}", "Completed").bold(),
+                " ".into(),
+                format!(
+                    "in {}",
+                    format_duration(completion_time.num_minutes() as i32)
+                )
+                .into(),
+            ]));
+        }
+
+        // Show when the work on the task is projected to start and finish
+        let forecasts = self.task_list.forecast(Local::now().naive_local());
+        if let Some(forecast) = forecasts.get(pos).copied().flatten() {
+            lines.push(Line::from(vec![
+                format!("{:<11}", "Starts").bold(),
+                " ".into(),
+                forecast.start.format("%Y-%m-%d %H:%M").to_string().into(),
+            ]));
+            let finish = forecast.finish.format("%Y-%m-%d %H:%M").to_string();
+            lines.push(Line::from(vec![
+                format!("{:<11}", "Finishes").bold(),
+                " ".into(),
+                match forecast.late {
+                    true => format!("{} (after it is due)", finish).red(),
+                    false => finish.into(),
+                },
+            ]));
+        }
+
+        // Warn when the task is due after one of its parents
+        if let Some(parent) = self.task_list.due_date_conflict(pos) {
+            let parent = &self.task_list.tasks[parent];
+            lines.push(Line::from(""));
+            lines.push(
+                Line::from(format!(
+                    "Due after '{}', which is due {}",
+                    parent.title,
+                    parent
+                        .due_date
+                        .as_ref()
+                        .map_or_else(String::new, format_date)
+                ))
+                .yellow(),
+            );
+        }
+
+        if let Some(error) = state.and_then(|state| state.error.clone()) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(error).red());
+        }
+
+        // Leave the rest of the pane below the fields for the description
+        let fields_height = (lines.len() as u16 + 1).min(inner_area.height);
+        let [fields_area, description_area] =
+            Layout::vertical([Constraint::Length(fields_height), Constraint::Fill(1)])
+                .areas(inner_area);
+        Paragraph::new(lines).render(fields_area, buf);
+
+        // Render the input over the value of the field being edited
+        let input = state.and_then(|state| state.input.as_ref());
+        if let (Some(row), Some(input)) = (input_row, input) {
+            let offset = 12.min(inner_area.width);
+            let y = inner_area.y + row as u16;
+            if y < inner_area.bottom() {
+                let input_area = Rect::new(inner_area.x + offset, y, inner_area.width - offset, 1);
+                render_input(input, input_area, buf);
+            }
+        }
+
+        self.render_description(description_area, buf);
+    }
+
+    /// Renders the description of the selected task as Markdown, or the description editor if it
+    /// is open.
+    fn render_description(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(description) = self.selected_task().map(|task| task.description.clone()) else {
+            return;
+        };
+        let scroll = match &mut self.state.mode {
+            TaskListMode::Detail(TaskDetailState {
+                editor: Some(editor),
+                ..
+            }) => {
+                editor.render(area, buf);
+                return;
+            }
+            TaskListMode::Detail(state) => state.description_scroll,
+            _ => 0,
+        };
+
+        Paragraph::new(markdown_lines(&description))
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .render(area, buf);
+    }
+}
+
+/// Gets the label of a field in the detail pane.
+fn field_label(field: DetailField) -> &'static str {
+    match field {
+        DetailField::Description => "Description",
+        DetailField::Status => "Status",
+        DetailField::ExecutionOrder => "Order",
+        DetailField::StartDate => "Start",
+        DetailField::DueDate => "Due",
+        DetailField::ExpectedDuration => "Estimate",
+    }
+}
+
+/// Gets the value of a field of a task as it is shown in the detail pane.
+fn field_value(task: &Task, field: DetailField) -> String {
+    let none = || String::from("-");
+    match field {
+        DetailField::Description => match task.description.lines().count() {
+            0 => none(),
+            1 => String::from("1 line"),
+            count => format!("{} lines", count),
+        },
+        DetailField::Status => match &task.task_status {
+            TaskStatus::NotStarted => String::from("Not started"),
+            TaskStatus::InProgress(time) => format!("In progress since {}", format_date_time(time)),
+            TaskStatus::Complete(time) => format!("Complete at {}", format_date_time(time)),
+        },
+        DetailField::ExecutionOrder => match task.execution_order {
+            ExecutionOrder::Series => String::from("Series"),
+            ExecutionOrder::Parallel => String::from("Parallel"),
+        },
+        DetailField::StartDate => task.start_date.as_ref().map_or_else(none, format_task_date),
+        DetailField::DueDate => task.due_date.as_ref().map_or_else(none, format_task_date),
+        DetailField::ExpectedDuration => task.expected_duration.map_or_else(none, format_duration),
+    }
+}
+
+/// Formats a date of a task along with how far it is from today.
+fn format_task_date(date: &DateTime<Local>) -> String {
+    let today = Local::now().date_naive();
+    format!(
+        "{} ({})",
+        format_date(date),
+        format_relative_date(date.date_naive(), today)
+    )
+}
+
+/// Parses a date such as `tomorrow`, `fri`, `+3d` or `YYYY-MM-DD`. An empty value clears the
+/// date.
+fn parse_task_date(value: &str) -> Result<Option<DateTime<Local>>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let date = parse_date(value, Local::now().date_naive()).ok_or_else(|| {
+        format!(
+            "'{}' is not a date such as today, fri, +3d or YYYY-MM-DD",
+            value
+        )
+    })?;
+    let date_time = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&date_time)
+        .earliest()
+        .map(Some)
+        .ok_or_else(|| format!("'{}' is not a valid local date", value))
+}
+
+/// Parses an estimate such as `90m`, `1h30` or `2d` into minutes. An empty value clears the
+/// estimate.
+fn parse_estimate(value: &str) -> Result<Option<i32>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let minutes = parse_duration(value)
+        .filter(|&minutes| minutes >= 0)
+        .ok_or_else(|| format!("'{}' is not a duration such as 90m, 1h30 or 2d", value))?;
+    match minutes > MAX_ESTIMATE {
+        true => Err(format!(
+            "'{}' is longer than the longest estimate of {}",
+            value,
+            format_duration(MAX_ESTIMATE)
+        )),
+        false => Ok(Some(minutes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::KeyEventHandler;
+    use crate::task_list_controller::TaskListController;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn press(controller: &mut TaskListController, code: KeyCode) {
+        controller.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn edited_estimate_is_saved_and_undoable() {
+        let mut controller = TaskListController::new(crate::task_list::tests::setup_task_list());
+        controller.state.selected_pos = 2;
+
+        press(&mut controller, KeyCode::Char('e'));
+        for _ in 0..5 {
+            press(&mut controller, KeyCode::Char('j'));
+        }
+        press(&mut controller, KeyCode::Enter);
+        press(&mut controller, KeyCode::Char('x'));
+        press(&mut controller, KeyCode::Enter);
+        assert!(controller.is_capturing_input());
+
+        press(&mut controller, KeyCode::Backspace);
+        press(&mut controller, KeyCode::Char('4'));
+        press(&mut controller, KeyCode::Char('5'));
+        press(&mut controller, KeyCode::Enter);
+        assert!(!controller.is_capturing_input());
+        assert_eq!(
+            controller.task_list.get_task(2).unwrap().expected_duration,
+            Some(45)
+        );
+
+        controller.task_list.undo();
+        assert_eq!(
+            controller.task_list.get_task(2).unwrap().expected_duration,
+            None
+        );
+    }
+
+    #[test]
+    fn estimates_are_limited() {
+        assert_eq!(super::parse_estimate("1000h"), Ok(Some(60000)));
+        assert_eq!(
+            super::parse_estimate("99999999h"),
+            Err(String::from(
+                "'99999999h' is longer than the longest estimate of 1000h"
+            ))
+        );
+    }
+
+    #[test]
+    fn description_is_edited_over_several_lines() {
+        let mut controller = TaskListController::new(crate::task_list::tests::setup_task_list());
+        controller.state.selected_pos = 1;
+
+        press(&mut controller, KeyCode::Char('n'));
+        for code in [
+            KeyCode::Char('a'),
+            KeyCode::Enter,
+            KeyCode::Char('q'),
+            KeyCode::Esc,
+        ] {
+            press(&mut controller, code);
+        }
+        assert!(!controller.is_capturing_input());
+        assert_eq!(
+            controller.task_list.get_task(1).unwrap().description,
+            "a\nq"
+        );
+
+        // Discarding the editor leaves the description unchanged
+        press(&mut controller, KeyCode::Enter);
+        press(&mut controller, KeyCode::Char('x'));
+        controller.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert_eq!(
+            controller.task_list.get_task(1).unwrap().description,
+            "a\nq"
+        );
+    }
+}
@@ -1,68 +1,322 @@
 ﻿use crate::app::KeyEventHandler;
-use crate::task_list_controller::state::{NewTaskDirection, NewTaskState, TaskListMode};
+use crate::task_list::Direction;
+use crate::task_list_controller::state::{
+    EditTaskState, NewTaskDirection, NewTaskState, TaskListMode, TaskSelectionState,
+};
 use crate::task_list_controller::TaskListController;
-use crossterm::event::{KeyCode, KeyEvent};
+use crate::ui::input::to_input_request;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Where the cursor is placed when starting to edit a task title.
+enum EditCursor {
+    /// At the start of the existing title.
+    Start,
+    /// At the end of the existing title.
+    End,
+    /// The existing title is cleared.
+    Clear,
+}
 
 impl KeyEventHandler for TaskListController {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         match self.state.mode {
-            TaskListMode::Normal => match key_event.code {
-                KeyCode::Char('j') => self.move_cursor_down(),
-                KeyCode::Char('k') => self.move_cursor_up(),
-                KeyCode::Char('o') => self.start_new_task_mode(NewTaskDirection::Below),
-                KeyCode::Char('O') => self.start_new_task_mode(NewTaskDirection::Above),
-                KeyCode::Tab => self.demote_task(),
-                KeyCode::BackTab => self.promote_task(),
-                KeyCode::Char(' ') => self.toggle_task_status(),
-                KeyCode::Char('d') => self.delete_task(),
-                KeyCode::Char('t') => self.toggle_execution_order(),
-                KeyCode::Char('T') => self.test(),
-                _ => {}
+            TaskListMode::Normal => match (self.state.pending_key.take(), key_event.code) {
+                (Some('g'), KeyCode::Char('g')) => self.move_cursor_to_top(),
+                (_, KeyCode::Char('g')) => self.state.pending_key = Some('g'),
+                (_, code) => self.handle_normal_key(code, key_event.modifiers),
             },
-            TaskListMode::New(_) => match key_event.code {
+            TaskListMode::New(ref mut state) => match key_event.code {
                 KeyCode::Enter => self.new_task(),
-                _ => {}
+                KeyCode::Esc => self.state.mode = TaskListMode::Normal,
+                _ => {
+                    if let Some(request) = to_input_request(&key_event) {
+                        state.input.handle(request);
+                    }
+                }
             },
-            TaskListMode::Edit(_) => match key_event.code {
-                KeyCode::Enter => {
-                    todo!();
+            TaskListMode::Edit(ref mut state) => match key_event.code {
+                KeyCode::Enter => self.edit_task(),
+                KeyCode::Esc => self.state.mode = TaskListMode::Normal,
+                _ => {
+                    if let Some(request) = to_input_request(&key_event) {
+                        state.input.handle(request);
+                    }
+                }
+            },
+            TaskListMode::Detail(_) => self.handle_detail_key_event(key_event),
+            TaskListMode::Selection(_) => match key_event.code {
+                KeyCode::Char('j') if key_event.modifiers.contains(KeyModifiers::ALT) => {
+                    self.move_selection(Direction::Down)
+                }
+                KeyCode::Char('k') if key_event.modifiers.contains(KeyModifiers::ALT) => {
+                    self.move_selection(Direction::Up)
+                }
+                KeyCode::Char('j') => {
+                    self.move_cursor_down();
+                    self.extend_selection();
                 }
+                KeyCode::Char('k') => {
+                    self.move_cursor_up();
+                    self.extend_selection();
+                }
+                KeyCode::Tab => self.demote_selection(),
+                KeyCode::BackTab => self.promote_selection(),
+                KeyCode::Char(' ') => self.toggle_selection_status(),
+                KeyCode::Char('d') => self.delete_selection(),
+                KeyCode::Char('t') => self.toggle_selection_execution_order(),
+                KeyCode::Char('v') | KeyCode::Esc => self.state.mode = TaskListMode::Normal,
                 _ => {}
             },
-            TaskListMode::Selection(_) => {}
         }
     }
 }
 
 impl TaskListController {
-    /// Moves the cursor up in the task list by one
+    /// Handles a key press in normal mode.
+    fn handle_normal_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => self.redo(),
+            KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.move_cursor_half_page(Direction::Down)
+            }
+            KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.move_cursor_half_page(Direction::Up)
+            }
+            KeyCode::Char('j') if modifiers.contains(KeyModifiers::ALT) => {
+                self.move_task(Direction::Down)
+            }
+            KeyCode::Char('k') if modifiers.contains(KeyModifiers::ALT) => {
+                self.move_task(Direction::Up)
+            }
+            KeyCode::Char('j') => self.move_cursor_down(),
+            KeyCode::Char('J') => self.move_cursor_to_next_sibling(),
+            KeyCode::Char('k') => self.move_cursor_up(),
+            KeyCode::Char('K') => self.move_cursor_to_previous_sibling(),
+            KeyCode::Char('h') => self.move_cursor_to_parent(),
+            KeyCode::Char('l') => self.move_cursor_to_child(),
+            KeyCode::Char('G') => self.move_cursor_to_bottom(),
+            KeyCode::Char('i') => self.start_edit_task_mode(EditCursor::Start),
+            KeyCode::Char('a') => self.start_edit_task_mode(EditCursor::End),
+            KeyCode::Char('c') => self.start_edit_task_mode(EditCursor::Clear),
+            KeyCode::Char('o') => self.start_new_task_mode(NewTaskDirection::Below),
+            KeyCode::Char('O') => self.start_new_task_mode(NewTaskDirection::Above),
+            KeyCode::Tab => self.demote_task(),
+            KeyCode::BackTab => self.promote_task(),
+            KeyCode::Char(' ') => self.toggle_task_status(),
+            KeyCode::Char('s') => self.start_task(),
+            KeyCode::Char('z') => self.pause_task(),
+            KeyCode::Char('S') => self.complete_task(),
+            KeyCode::Char('d') => self.delete_task(),
+            KeyCode::Char('t') => self.toggle_execution_order(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('v') => self.start_selection_mode(),
+            KeyCode::Char('e') => self.start_detail_mode(),
+            KeyCode::Char('n') => self.start_description_editor(),
+            KeyCode::Char('m') => self.start_estimate_editor(),
+            KeyCode::Char('f') => self.toggle_fold(),
+            KeyCode::Char('F') => self.fold_all(),
+            KeyCode::Char('E') => self.unfold_all(),
+            KeyCode::Char(c @ '1'..='9') => self.fold_to_depth(c as i8 - '0' as i8),
+            KeyCode::Char('b') => self.state.timeline = !self.state.timeline,
+            KeyCode::Char('+') | KeyCode::Char('=') => self.state.zoom = self.state.zoom.zoom_in(),
+            KeyCode::Char('-') => self.state.zoom = self.state.zoom.zoom_out(),
+            KeyCode::Char('T') => self.test(),
+            _ => {}
+        }
+    }
+
+    /// Moves the cursor up in the task list by one visible task
     fn move_cursor_up(&mut self) {
-        let state = &mut self.state;
-        if state.selected_pos != 0 {
-            state.selected_pos -= 1;
+        let visible_tasks = &self.task_list.visible_tasks;
+        let index = visible_tasks.partition_point(|&pos| pos < self.state.selected_pos);
+        if index > 0 {
+            self.state.selected_pos = visible_tasks[index - 1];
         }
     }
 
-    /// Moves the cursor down in the task list by one
+    /// Moves the cursor down in the task list by one visible task
     fn move_cursor_down(&mut self) {
-        let state = &mut self.state;
-        let task_list = &self.task_list;
-        if state.selected_pos < task_list.len() - 1 {
-            state.selected_pos += 1;
+        let visible_tasks = &self.task_list.visible_tasks;
+        let index = visible_tasks.partition_point(|&pos| pos <= self.state.selected_pos);
+        if let Some(&pos) = visible_tasks.get(index) {
+            self.state.selected_pos = pos;
+        }
+    }
+
+    /// Moves the cursor up or down by half the height of the view
+    fn move_cursor_half_page(&mut self, dir: Direction) {
+        let visible_tasks = &self.task_list.visible_tasks;
+        if visible_tasks.is_empty() {
+            return;
+        }
+
+        let index = visible_tasks.partition_point(|&pos| pos < self.state.selected_pos);
+        let distance = (self.state.viewport_height / 2).max(1);
+        let index = match dir {
+            Direction::Up => index.saturating_sub(distance),
+            _ => (index + distance).min(visible_tasks.len() - 1),
+        };
+        self.state.selected_pos = visible_tasks[index];
+    }
+
+    /// Moves the cursor to the parent of the selected task
+    fn move_cursor_to_parent(&mut self) {
+        if let Some(parent) = self.task_list.get_parent(self.state.selected_pos) {
+            self.state.selected_pos = parent;
+        }
+    }
+
+    /// Moves the cursor to the first subtask of the selected task, unfolding it if needed
+    fn move_cursor_to_child(&mut self) {
+        let pos = self.state.selected_pos;
+        let Some(&child) = self.task_list.get_direct_subtasks(pos).first() else {
+            return;
+        };
+        if !self.task_list.is_visible(child) {
+            self.task_list.toggle_fold(pos);
+        }
+        self.state.selected_pos = child;
+    }
+
+    /// Moves the cursor to the next sibling of the selected task, skipping over its subtasks
+    fn move_cursor_to_next_sibling(&mut self) {
+        if let Some(sibling) = self.task_list.get_next_sibling(self.state.selected_pos) {
+            self.state.selected_pos = sibling;
+        }
+    }
+
+    /// Moves the cursor to the previous sibling of the selected task, skipping over its subtasks
+    fn move_cursor_to_previous_sibling(&mut self) {
+        if let Some(sibling) = self.task_list.get_previous_sibling(self.state.selected_pos) {
+            self.state.selected_pos = sibling;
+        }
+    }
+
+    /// Moves the cursor to the first task in the list
+    fn move_cursor_to_top(&mut self) {
+        if let Some(&pos) = self.task_list.visible_tasks.first() {
+            self.state.selected_pos = pos;
+        }
+    }
+
+    /// Moves the cursor to the last visible task in the list
+    fn move_cursor_to_bottom(&mut self) {
+        if let Some(&pos) = self.task_list.visible_tasks.last() {
+            self.state.selected_pos = pos;
+        }
+    }
+
+    fn toggle_fold(&mut self) {
+        self.task_list.toggle_fold(self.state.selected_pos);
+    }
+
+    fn fold_all(&mut self) {
+        self.task_list.fold_all();
+        self.select_visible();
+    }
+
+    fn unfold_all(&mut self) {
+        self.task_list.unfold_all();
+    }
+
+    /// Folds the list so that only the tasks above the given depth are shown.
+    fn fold_to_depth(&mut self, depth: i8) {
+        self.task_list.fold_to_depth(depth);
+        self.select_visible();
+    }
+
+    /// Moves the selection out to the nearest visible parent if it is hidden inside a folded task.
+    fn select_visible(&mut self) {
+        while !self.task_list.is_visible(self.state.selected_pos) {
+            let Some(parent) = self.task_list.get_parent(self.state.selected_pos) else {
+                return;
+            };
+            self.state.selected_pos = parent;
         }
     }
 
     fn start_new_task_mode(&mut self, dir: NewTaskDirection) {
+        // An empty list can always have a first task added to it
+        let depth = self.selected_task().map_or(0, |task| task.depth);
+
+        let state = NewTaskState::new(depth, dir);
+        self.state.mode = TaskListMode::New(state);
+    }
+
+    /// Gets the position that the task being entered will be inserted at.
+    pub(crate) fn new_task_pos(&self) -> usize {
+        let TaskListMode::New(state) = &self.state.mode else {
+            return self.state.selected_pos;
+        };
+        if self.task_list.len() == 0 {
+            return 0;
+        }
+
+        let pos = self.state.selected_pos;
+        match state.dir {
+            NewTaskDirection::Above => pos,
+            // Add the task below the whole subtree of the selected task so that it stays a sibling
+            NewTaskDirection::Below => self.task_list.get_last_subtask_pos(pos) + 1,
+        }
+    }
+
+    fn start_edit_task_mode(&mut self, cursor: EditCursor) {
         let Some(task) = self.selected_task() else {
             return;
         };
 
-        let state = NewTaskState::new(task.depth, dir);
-        self.state.mode = TaskListMode::New(state);
+        let title = match cursor {
+            EditCursor::Clear => String::new(),
+            _ => task.title.clone(),
+        };
+        let cursor = match cursor {
+            EditCursor::Start => 0,
+            _ => title.chars().count(),
+        };
+        let state = EditTaskState::new(self.state.selected_pos, title, cursor);
+        self.state.mode = TaskListMode::Edit(state);
     }
 
+    /// Commits the edited title back to the task and returns to normal mode.
+    fn edit_task(&mut self) {
+        let TaskListMode::Edit(state) = &self.state.mode else {
+            return;
+        };
+
+        // Leave the title unchanged rather than leaving a blank task
+        let title = state.input.value().trim();
+        if !title.is_empty() {
+            self.task_list.set_task_title(state.pos, title);
+        }
+        self.state.mode = TaskListMode::Normal;
+    }
+
+    /// Inserts the entered task and starts entering its next sibling. An empty title finishes
+    /// entering tasks.
     fn new_task(&mut self) {
-        todo!();
+        let TaskListMode::New(state) = &self.state.mode else {
+            return;
+        };
+
+        let title = state.input.value().trim().to_string();
+        if title.is_empty() {
+            self.state.mode = TaskListMode::Normal;
+            return;
+        }
+
+        let depth = state.depth;
+        let pos = self.new_task_pos();
+        self.task_list.add_new_task(&title, pos, depth);
+        self.state.selected_pos = pos;
+        self.state.mode = TaskListMode::New(NewTaskState::new(depth, NewTaskDirection::Below));
+    }
+
+    /// Moves the selected task and its subtasks up or down past its sibling, keeping it selected.
+    fn move_task(&mut self, dir: Direction) {
+        let pos = self.state.selected_pos;
+        if let Some(new_pos) = self.task_list.move_tasks(pos, pos, &dir) {
+            self.state.selected_pos = new_pos;
+        }
     }
 
     fn promote_task(&mut self) {
@@ -77,6 +331,18 @@ impl TaskListController {
         self.task_list.toggle_task_status(self.state.selected_pos);
     }
 
+    fn start_task(&mut self) {
+        self.task_list.start_task(self.state.selected_pos);
+    }
+
+    fn pause_task(&mut self) {
+        self.task_list.pause_task(self.state.selected_pos);
+    }
+
+    fn complete_task(&mut self) {
+        self.task_list.complete_task(self.state.selected_pos);
+    }
+
     fn toggle_execution_order(&mut self) {
         self.task_list
             .toggle_execution_order(self.state.selected_pos);
@@ -86,6 +352,105 @@ impl TaskListController {
         self.task_list.delete_task(self.state.selected_pos);
     }
 
+    fn start_selection_mode(&mut self) {
+        if self.selected_task().is_none() {
+            return;
+        }
+
+        let pos = self.state.selected_pos;
+        self.state.mode = TaskListMode::Selection(TaskSelectionState::new(pos, pos));
+    }
+
+    /// Moves the end of the selection to the cursor.
+    fn extend_selection(&mut self) {
+        if let TaskListMode::Selection(state) = &mut self.state.mode {
+            state.pos_end = self.state.selected_pos;
+        }
+    }
+
+    /// Gets the first and last positions of the selection, in order.
+    pub(crate) fn selection_range(&self) -> Option<(usize, usize)> {
+        let TaskListMode::Selection(state) = &self.state.mode else {
+            return None;
+        };
+        Some((
+            state.pos_start.min(state.pos_end),
+            state.pos_start.max(state.pos_end),
+        ))
+    }
+
+    /// Deletes the selected tasks along with their subtasks and leaves selection mode.
+    fn delete_selection(&mut self) {
+        let Some((start, end)) = self.selection_range() else {
+            return;
+        };
+        self.task_list.delete_tasks(start, end);
+        self.state.mode = TaskListMode::Normal;
+        self.select_pos(start);
+    }
+
+    fn toggle_selection_status(&mut self) {
+        if let Some((start, end)) = self.selection_range() {
+            self.task_list.toggle_tasks_status(start, end);
+        }
+    }
+
+    fn toggle_selection_execution_order(&mut self) {
+        if let Some((start, end)) = self.selection_range() {
+            self.task_list.toggle_tasks_execution_order(start, end);
+        }
+    }
+
+    fn promote_selection(&mut self) {
+        if let Some((start, end)) = self.selection_range() {
+            self.task_list.promote_tasks(start, end);
+        }
+    }
+
+    fn demote_selection(&mut self) {
+        if let Some((start, end)) = self.selection_range() {
+            self.task_list.demote_tasks(start, end);
+        }
+    }
+
+    /// Moves the selected tasks past their neighbouring sibling, keeping them selected.
+    fn move_selection(&mut self, dir: Direction) {
+        let Some((start, end)) = self.selection_range() else {
+            return;
+        };
+        let Some(new_start) = self.task_list.move_tasks(start, end, &dir) else {
+            return;
+        };
+
+        let TaskListMode::Selection(state) = &mut self.state.mode else {
+            return;
+        };
+        state.pos_start = state.pos_start + new_start - start;
+        state.pos_end = state.pos_end + new_start - start;
+        self.state.selected_pos = state.pos_end;
+    }
+
+    /// Undoes the last change to the task list and selects the task that it affected.
+    fn undo(&mut self) {
+        if let Some(pos) = self.task_list.undo() {
+            self.select_pos(pos);
+        }
+    }
+
+    /// Redoes the last undone change to the task list and selects the task that it affected.
+    fn redo(&mut self) {
+        if let Some(pos) = self.task_list.redo() {
+            self.select_pos(pos);
+        }
+    }
+
+    /// Selects the task at a position, keeping the selection within the list and out of any
+    /// folded tasks.
+    pub(crate) fn select_pos(&mut self, pos: usize) {
+        self.state.selected_pos = pos.min(self.task_list.len().saturating_sub(1));
+        self.select_visible();
+    }
+
     /// Temporary test function that creates a task list with some tasks
     fn test(&mut self) {
         let task_list = &mut self.task_list;
@@ -96,3 +461,38 @@ impl TaskListController {
         task_list.add_new_subtask("Task 1.1.1", 1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::app::KeyEventHandler;
+    use crate::task_list::TaskList;
+    use crate::task_list_controller::TaskListController;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn moving_a_task_takes_its_subtasks_past_the_sibling_and_its_subtasks() {
+        let mut task_list = TaskList::new("Task List");
+        task_list.add_new_root_task_at_end("A");
+        task_list.add_new_root_task_at_end("B");
+        task_list.add_new_subtask("B.1", 1);
+        let mut controller = TaskListController::new(task_list);
+        controller.state.selected_pos = 1;
+        let titles = |controller: &TaskListController| -> Vec<String> {
+            controller
+                .task_list
+                .tasks
+                .iter()
+                .map(|task| task.title.clone())
+                .collect()
+        };
+
+        controller.handle_key_event(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::ALT));
+        assert_eq!(titles(&controller), vec!["B", "B.1", "A"]);
+        assert_eq!(controller.task_list.get_direct_subtasks(0), vec![1]);
+        assert_eq!(controller.state.selected_pos, 0);
+
+        controller.handle_key_event(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::ALT));
+        assert_eq!(titles(&controller), vec!["A", "B", "B.1"]);
+        assert_eq!(controller.state.selected_pos, 1);
+    }
+}
@@ -1,26 +1,111 @@
 ﻿use crate::task_list_controller::state::TaskListMode;
 use crate::task_list_controller::TaskListController;
+use crate::ui::format::{format_duration, format_relative_date};
+use crate::ui::input::render_input;
 use crate::ui::joiner::render_joiner;
 use crate::{task::render::TaskState, task_list::Direction};
-use ratatui::style::Stylize;
-use ratatui::text::Text;
-use ratatui::widgets::{Block, Paragraph, Widget};
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::widgets::Widget;
 use ratatui::{buffer::Buffer, layout::Rect, prelude::StatefulWidget};
+use std::collections::HashMap;
 
-impl Widget for &TaskListController {
+/// A single row of the rendered task list.
+#[derive(Clone, Copy, PartialEq)]
+enum Row {
+    /// The task at the position in the list.
+    Task(usize),
+    /// The task that is currently being entered.
+    NewTask,
+}
+
+impl Widget for &mut TaskListController {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        // Render the task list without joiners
+        let rows = self.rows();
+        if self.state.timeline {
+            // Leave the top row for the time scale
+            let [scale_area, area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+            self.scroll_to_selection(&rows, area.height as usize);
+            self.render_timeline(&rows, scale_area, area, buf, Local::now().naive_local());
+            return;
+        }
+
+        self.scroll_to_selection(&rows, area.height as usize);
         let task_list = &self.task_list;
-        let mut y: u16 = area.y;
 
-        // Keep track of the x and y coordinates of all rendered tasks
-        let mut rendered_tasks: Vec<(u16, u16, usize)> = Vec::new();
+        // Get the y coordinate of every row, including the rows that are scrolled out of view so
+        // that joiners can be drawn through the edges of the area.
+        let offset = self.state.scroll_offset as i32;
+        let row_y = |row: usize| area.y as i32 + row as i32 - offset;
+        let task_y: HashMap<usize, i32> = rows
+            .iter()
+            .enumerate()
+            .filter_map(|(row, &task_row)| match task_row {
+                Row::Task(pos) => Some((pos, row_y(row))),
+                Row::NewTask => None,
+            })
+            .collect();
+        let on_screen = |y: i32| y >= area.y as i32 && y < area.bottom() as i32;
+
+        let selection = self.selection_range();
+        let now = Local::now().naive_local();
+        // Work out the forecast and estimates of every task once, rather than for each row
+        let forecasts = task_list.forecast(now);
+        let estimates = task_list.calculate_task_durations();
+        let remaining = task_list.calculate_remaining_durations();
 
-        // Render each task in the task list
-        for (pos, task) in task_list.tasks.iter().enumerate() {
-            let x = (task.depth * 3) as u16 + area.x;
+        // Render the tasks in view without joiners
+        for (row, &task_row) in rows.iter().enumerate() {
+            let Row::Task(pos) = task_row else {
+                continue;
+            };
+            let y = row_y(row);
+            if !on_screen(y) {
+                continue;
+            }
+            let y = y as u16;
+            let Ok(task) = task_list.get_task(pos) else {
+                continue;
+            };
+
+            let indent = (task.depth as u16 * 3).min(area.width);
             // Create the area that the task will be rendered in and render the task
-            let task_area = Rect::new(x, y, area.width - task.depth as u16 * 3, 1);
+            let mut task_area = Rect::new(area.x + indent, y, area.width - indent, 1);
+
+            // Show the estimate at the right of the row, along with the work left on branch tasks
+            // and when the work is projected to finish
+            let estimate = estimates[pos];
+            if estimate > 0 {
+                if let Some(forecast) = forecasts.get(pos).copied().flatten() {
+                    let column = format!(
+                        "{:>9}",
+                        format_relative_date(forecast.finish.date(), now.date())
+                    );
+                    let style = match forecast.late {
+                        true => Style::default().red(),
+                        false => Style::default().dark_gray(),
+                    };
+                    let width = (column.chars().count() as u16).min(task_area.width);
+                    let x = task_area.right() - width;
+                    buf.set_stringn(x, y, &column, width as usize, style);
+                    task_area.width = task_area.width.saturating_sub(width + 1);
+                }
+
+                let column = match task_list.has_subtasks(pos) {
+                    true => format!(
+                        "{} left of {}",
+                        format_duration(remaining[pos]),
+                        format_duration(estimate)
+                    ),
+                    false => format_duration(estimate),
+                };
+                let width = (column.chars().count() as u16).min(task_area.width);
+                let x = task_area.right() - width;
+                buf.set_stringn(x, y, &column, width as usize, Style::default().dark_gray());
+                task_area.width = task_area.width.saturating_sub(width + 1);
+            }
 
             // Create a state for each rendered task based on the current state
             let mut task_state = TaskState::default();
@@ -31,59 +116,334 @@ impl Widget for &TaskListController {
             if task_list.neighbour_depth(pos, &Direction::Down) <= task.depth {
                 task_state.leaf = true;
             }
+            // A folded task is drawn as a leaf task, as none of its subtasks are joined to it
+            task_state.hidden_subtasks = task_list.hidden_subtask_count(pos);
+            task_state.percent_complete = task_list.percent_complete(pos);
+            task_state.tracking = task.is_tracking();
+            task_state.due_conflict = task_list.due_date_conflict(pos).is_some();
+            if task_state.hidden_subtasks > 0 {
+                task_state.leaf = true;
+            }
             if self.state.selected_pos == pos {
                 task_state.selected = true;
             }
+            if let Some((start, end)) = selection {
+                task_state.selected = (start..=end).contains(&pos);
+            }
 
-            // Render each task and keep track of what has been rendered
             task.render(task_area, buf, &mut task_state);
-            rendered_tasks.push((x, y, pos));
 
-            y += 1;
-            // TODO: Stop rendering once outside the main area
+            // Render the title editor over the title of the task being edited
+            if let TaskListMode::Edit(state) = &self.state.mode {
+                if state.pos == pos {
+                    let offset = task_state.title_offset().min(task_area.width);
+                    let input_area =
+                        Rect::new(task_area.x + offset, y, task_area.width - offset, 1);
+                    render_input(&state.input, input_area, buf);
+                }
+            }
         }
 
-        // Render subtask joiners
-        for (x, y, pos) in &rendered_tasks {
-            // If there are no subtasks, do nothing further
-            if !task_list.has_subtasks(*pos) {
+        // Render subtask joiners, including those of parents and subtasks that are out of view
+        for (&pos, &y) in &task_y {
+            // Tasks below the area cannot have any joiners in view
+            if y >= area.bottom() as i32 {
                 continue;
             }
-            // Get the top position of the task
-            let top = *y;
-            let mut rendered_subtasks: Vec<u16> = vec![];
-
-            // Get the direct subtasks of this task that have been rendered to the screen
-            let subtasks = task_list.get_direct_subtasks(*pos);
-            for subtask_pos in subtasks {
-                // Find the subtasks that have been rendered
-                let Some((_, subtask_y, _)) =
-                    &rendered_tasks.iter().find(|(_, _, p)| *p == subtask_pos)
-                else {
-                    continue;
-                };
-
-                // Keep a vector of the positions of all rendered subtasks.
-                rendered_subtasks.push(*subtask_y);
+            // If there are no visible subtasks, do nothing further
+            if !task_list.has_subtasks(pos) || task_list.hidden_subtask_count(pos) > 0 {
+                continue;
             }
-
-            let Ok(task) = task_list.get_task(*pos) else {
+            let Ok(task) = task_list.get_task(pos) else {
                 continue;
             };
 
+            // Get the positions of all the direct subtasks of this task
+            let subtask_ys: Vec<i32> = task_list
+                .get_direct_subtasks(pos)
+                .iter()
+                .filter_map(|subtask_pos| task_y.get(subtask_pos).copied())
+                .collect();
+            // Joiners that end above the area are entirely out of view
+            if subtask_ys.last().is_none_or(|&last| last < area.y as i32) {
+                continue;
+            }
+
             // Render the joiner
-            render_joiner(*x, *y, rendered_subtasks, buf, &task.execution_order);
+            let x = area.x + (task.depth as u16 * 3).min(area.width);
+            render_joiner(x, y, subtask_ys, area, buf, &task.execution_order);
+        }
+
+        // Render the task being entered once the joiners are drawn so that it sits on top of them
+        if let Some(row) = rows.iter().position(|&row| row == Row::NewTask) {
+            let y = row_y(row);
+            if on_screen(y) {
+                self.render_new_task(area, y as u16, buf);
+            }
         }
     }
 }
 
 impl TaskListController {
-    fn render_input(&mut self, area: Rect, buf: &mut Buffer) {
-        if let TaskListMode::New(state) = &self.state.mode {
-            let text: Text = state.title.clone().into();
-            let block = Block::default().on_dark_gray();
-            let input = Paragraph::new(text).block(block);
-            input.render(area, buf);
+    /// Gets the rows of the task list in the order that they are rendered, leaving out tasks that
+    /// are hidden inside folded tasks.
+    fn rows(&self) -> Vec<Row> {
+        let mut rows: Vec<Row> = self
+            .task_list
+            .visible_tasks
+            .iter()
+            .map(|&pos| Row::Task(pos))
+            .collect();
+
+        // Leave a row for the task being entered
+        if let TaskListMode::New(_) = self.state.mode {
+            let new_task_pos = self.new_task_pos();
+            let row = self
+                .task_list
+                .visible_tasks
+                .partition_point(|&pos| pos < new_task_pos);
+            rows.insert(row, Row::NewTask);
+        }
+        rows
+    }
+
+    /// Scrolls the list so that the selected row stays within the scroll-off margin of the edges
+    /// of an area of the given height.
+    fn scroll_to_selection(&mut self, rows: &[Row], height: usize) {
+        self.state.viewport_height = height;
+        if height == 0 {
+            return;
+        }
+
+        let selected_row = match self.state.mode {
+            TaskListMode::New(_) => rows.iter().position(|&row| row == Row::NewTask),
+            _ => rows
+                .iter()
+                .position(|&row| row == Row::Task(self.state.selected_pos)),
+        };
+        let state = &mut self.state;
+        if let Some(selected_row) = selected_row {
+            // Shrink the margin on small areas so that the selection can still move
+            let margin = state.scroll_off.min((height - 1) / 2);
+            if selected_row < state.scroll_offset + margin {
+                state.scroll_offset = selected_row.saturating_sub(margin);
+            }
+            if selected_row + margin >= state.scroll_offset + height {
+                state.scroll_offset = selected_row + margin + 1 - height;
+            }
+        }
+
+        // Don't leave empty space at the bottom of the area when the list could fill it
+        state.scroll_offset = state.scroll_offset.min(rows.len().saturating_sub(height));
+    }
+
+    /// Renders the tasks as bars along a timeline, positioned by when they are projected to start
+    /// and finish, with the titles of the tasks to the left of the bars.
+    fn render_timeline(
+        &self,
+        rows: &[Row],
+        scale_area: Rect,
+        area: Rect,
+        buf: &mut Buffer,
+        now: NaiveDateTime,
+    ) {
+        let task_list = &self.task_list;
+        let forecasts = task_list.forecast(now);
+        let zoom = self.state.zoom;
+        let selection = self.selection_range();
+
+        let label_width = (area.width / 3).min(30);
+        let chart_x = area.x + label_width;
+        let chart_width = (area.width - label_width) as i64;
+
+        // Move the timeline along by whole labels until the start of the selected task is in view
+        let period = zoom.minutes_per_column() * zoom.label_columns();
+        let mut origin = zoom.origin(now);
+        if let Some(forecast) = forecasts.get(self.state.selected_pos).copied().flatten() {
+            let column = zoom.column(origin, forecast.start);
+            if column >= chart_width {
+                origin += TimeDelta::minutes(period * (column / zoom.label_columns()));
+            }
         }
+
+        // Render the time scale
+        for label in 0.. {
+            let x = label * zoom.label_columns();
+            if x >= chart_width {
+                break;
+            }
+            let time = origin + TimeDelta::minutes(period * label);
+            let text = time.format(zoom.label_format()).to_string();
+            let width = zoom.label_columns().min(chart_width - x) as usize;
+            buf.set_stringn(
+                chart_x + x as u16,
+                scale_area.y,
+                text,
+                width,
+                Style::default().dark_gray(),
+            );
+        }
+
+        let offset = self.state.scroll_offset;
+        for (row, &task_row) in rows
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(area.height as usize)
+        {
+            let y = area.y + (row - offset) as u16;
+            let pos = match task_row {
+                Row::Task(pos) => pos,
+                Row::NewTask => {
+                    if let TaskListMode::New(state) = &self.state.mode {
+                        let indent = (state.depth as u16 * 2).min(label_width);
+                        let input_area = Rect::new(area.x + indent, y, label_width - indent, 1);
+                        render_input(&state.input, input_area, buf);
+                    }
+                    continue;
+                }
+            };
+            let Ok(task) = task_list.get_task(pos) else {
+                continue;
+            };
+
+            // Render the title with the same selection cursor as the tree
+            let selected = match selection {
+                Some((start, end)) => (start..=end).contains(&pos),
+                None => self.state.selected_pos == pos,
+            };
+            let indent = (task.depth as u16 * 2).min(label_width);
+            let title_area = Rect::new(area.x + indent, y, label_width - indent, 1);
+            match &self.state.mode {
+                TaskListMode::Edit(state) if state.pos == pos => {
+                    render_input(&state.input, title_area, buf)
+                }
+                _ => {
+                    let style = match selected {
+                        true => Style::default().reversed(),
+                        false => Style::default(),
+                    };
+                    let width = (title_area.width as usize).saturating_sub(1);
+                    buf.set_stringn(title_area.x, y, &task.title, width, style);
+                }
+            }
+
+            // Render the bar between the projected start and finish of the task, leaving out tasks
+            // with no work left and tasks that finish before the start of the timeline
+            let Some(forecast) = forecasts[pos].filter(|forecast| forecast.finish > forecast.start)
+            else {
+                continue;
+            };
+            let start = zoom.column(origin, forecast.start).max(0);
+            let finish =
+                (zoom.column(origin, forecast.finish - TimeDelta::minutes(1)) + 1).min(chart_width);
+            if start >= finish {
+                continue;
+            }
+            // Branch tasks are drawn thinner than the leaf tasks they are made up of
+            let symbol = match task_list.has_subtasks(pos) {
+                true => "▬",
+                false => "█",
+            };
+            let style = match task.is_critical {
+                true => Style::default().red(),
+                false => Style::default().blue(),
+            };
+            let bar = symbol.repeat((finish - start) as usize);
+            buf.set_string(chart_x + start as u16, y, bar, style);
+        }
+    }
+
+    /// Renders the task that is being entered as a new leaf task on the given row.
+    fn render_new_task(&self, area: Rect, y: u16, buf: &mut Buffer) {
+        let TaskListMode::New(state) = &self.state.mode else {
+            return;
+        };
+
+        let indent = (state.depth as u16 * 3).min(area.width);
+        let task_area = Rect::new(area.x + indent, y, area.width - indent, 1);
+        let task_state = TaskState {
+            leaf: true,
+            ..TaskState::default()
+        };
+        let offset = task_state.title_offset().min(task_area.width);
+
+        buf.set_string(task_area.x, y, "○", Style::default());
+        let input_area = Rect::new(task_area.x + offset, y, task_area.width - offset, 1);
+        render_input(&state.input, input_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task_list_controller::render::Row;
+    use crate::task_list_controller::state::Zoom;
+    use crate::task_list_controller::TaskListController;
+    use chrono::NaiveDate;
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+    #[test]
+    fn selection_is_scrolled_into_view() {
+        let mut controller = TaskListController::new(crate::task_list::tests::setup_task_list());
+        controller.state.scroll_off = 1;
+        controller.state.selected_pos = 8;
+
+        let area = Rect::new(0, 0, 20, 4);
+        let mut buf = Buffer::empty(area);
+        (&mut controller).render(area, &mut buf);
+
+        assert_eq!(controller.state.scroll_offset, 5);
+        let last_row: String = (0..area.width)
+            .map(|x| buf[(x, 3)].symbol().to_string())
+            .collect();
+        assert!(last_row.contains("Task 3"));
+
+        // Scrolling back up keeps a margin above the selection
+        controller.state.selected_pos = 4;
+        (&mut controller).render(area, &mut buf);
+        assert_eq!(controller.state.scroll_offset, 3);
+    }
+
+    #[test]
+    fn timeline_bars_follow_the_forecast() {
+        let mut controller = TaskListController::new(crate::task_list::tests::setup_task_list());
+        for (pos, duration) in [(1, 120), (2, 240), (3, 60)] {
+            controller
+                .task_list
+                .update_task(pos, |task| task.expected_duration = Some(duration));
+        }
+        controller.state.zoom = Zoom::Hours;
+
+        // Monday morning, at the start of the working day
+        let now = NaiveDate::from_ymd_opt(2024, 1, 1)
+            .and_then(|date| date.and_hms_opt(9, 0, 0))
+            .unwrap();
+        let rows: Vec<Row> = (0..5).map(Row::Task).collect();
+        let area = Rect::new(0, 1, 60, 5);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 60, 6));
+        controller.render_timeline(&rows, Rect::new(0, 0, 60, 1), area, &mut buf, now);
+
+        let row = |y: u16| -> String {
+            (20..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect()
+        };
+        assert!(row(0).starts_with("Mon 00:00"));
+        // Series subtasks follow one another from 09:00, with an hour for each column
+        assert_eq!(
+            row(1).trim_end(),
+            format!("{}{}", " ".repeat(9), "▬".repeat(7))
+        );
+        assert_eq!(
+            row(2).trim_end(),
+            format!("{}{}", " ".repeat(9), "█".repeat(2))
+        );
+        assert_eq!(
+            row(3).trim_end(),
+            format!("{}{}", " ".repeat(11), "█".repeat(4))
+        );
+        assert_eq!(row(4).trim_end(), format!("{}{}", " ".repeat(15), "█"));
+        // Tasks without an estimate have no bar
+        assert_eq!(row(5).trim_end(), "");
     }
 }
@@ -1,9 +1,118 @@
-﻿/// Contains the application state of the list.
-#[derive(Debug, Default, Clone)]
+﻿use crate::debug::SCROLL_OFF;
+use crate::ui::text_area::TextArea;
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime};
+use tui_input::Input;
+
+/// Contains the application state of the list.
+#[derive(Debug, Clone)]
 pub struct TaskListState {
     /// The position of the currently selected task in the list.
     pub(crate) selected_pos: usize,
     pub(crate) mode: TaskListMode,
+    /// The first key of a two key command that is waiting for its second key.
+    pub(crate) pending_key: Option<char>,
+    /// The number of rows that the list is scrolled down by.
+    pub(crate) scroll_offset: usize,
+    /// The minimum number of rows kept in view above and below the selected task.
+    pub(crate) scroll_off: usize,
+    /// The number of rows that the list was last rendered in.
+    pub(crate) viewport_height: usize,
+    /// True if the tasks are drawn as bars along a timeline rather than as a tree.
+    pub(crate) timeline: bool,
+    /// How much time each column of the timeline covers.
+    pub(crate) zoom: Zoom,
+}
+
+impl Default for TaskListState {
+    fn default() -> Self {
+        TaskListState {
+            selected_pos: 0,
+            mode: TaskListMode::default(),
+            pending_key: None,
+            scroll_offset: 0,
+            scroll_off: *SCROLL_OFF,
+            viewport_height: 0,
+            timeline: false,
+            zoom: Zoom::default(),
+        }
+    }
+}
+
+/// How much time each column of the timeline covers.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Zoom {
+    /// Each column covers an hour.
+    Hours,
+    /// Each column covers three hours.
+    #[default]
+    Days,
+    /// Each column covers a day.
+    Weeks,
+}
+
+impl Zoom {
+    /// Gets the next closer zoom level, staying on hours once it is reached.
+    pub(crate) fn zoom_in(self) -> Zoom {
+        match self {
+            Zoom::Hours | Zoom::Days => Zoom::Hours,
+            Zoom::Weeks => Zoom::Days,
+        }
+    }
+
+    /// Gets the next further zoom level, staying on weeks once it is reached.
+    pub(crate) fn zoom_out(self) -> Zoom {
+        match self {
+            Zoom::Hours => Zoom::Days,
+            Zoom::Days | Zoom::Weeks => Zoom::Weeks,
+        }
+    }
+
+    /// Gets the number of minutes that each column covers.
+    pub(crate) fn minutes_per_column(self) -> i64 {
+        match self {
+            Zoom::Hours => 60,
+            Zoom::Days => 3 * 60,
+            Zoom::Weeks => 24 * 60,
+        }
+    }
+
+    /// Gets the number of columns between each label of the time scale.
+    pub(crate) fn label_columns(self) -> i64 {
+        match self {
+            Zoom::Hours => 12,
+            Zoom::Days => 8,
+            Zoom::Weeks => 7,
+        }
+    }
+
+    /// Gets the format of the labels of the time scale.
+    pub(crate) fn label_format(self) -> &'static str {
+        match self {
+            Zoom::Hours => "%a %H:%M",
+            Zoom::Days => "%a %d",
+            Zoom::Weeks => "%b %d",
+        }
+    }
+
+    /// Gets the time that the timeline starts from, which is the start of the day, or the start
+    /// of the week when zoomed out to weeks.
+    pub(crate) fn origin(self, now: NaiveDateTime) -> NaiveDateTime {
+        let today = now.date();
+        let first_day = match self {
+            Zoom::Weeks => today
+                .checked_sub_days(Days::new(today.weekday().num_days_from_monday() as u64))
+                .unwrap_or(today),
+            Zoom::Hours | Zoom::Days => today,
+        };
+        first_day.and_time(NaiveTime::MIN)
+    }
+
+    /// Gets the column of the timeline starting at the origin that a time falls in.
+    pub(crate) fn column(self, origin: NaiveDateTime, time: NaiveDateTime) -> i64 {
+        (time - origin)
+            .num_minutes()
+            .div_euclid(self.minutes_per_column())
+    }
 }
 
 /// The mode that the application is currently in within the task list.
@@ -18,12 +127,14 @@ pub(crate) enum TaskListMode {
     Edit(EditTaskState),
     /// The user is selecting a continuous group of tasks.
     Selection(TaskSelectionState),
+    /// The user is viewing or editing the fields of the selected task in the detail pane.
+    Detail(TaskDetailState),
 }
 
 /// The state of the application when in task entry mode.
 #[derive(Debug, Clone)]
 pub struct NewTaskState {
-    pub(crate) title: String,
+    pub(crate) input: Input,
     pub(crate) depth: i8,
     pub(crate) dir: NewTaskDirection,
 }
@@ -31,28 +142,33 @@ pub struct NewTaskState {
 impl NewTaskState {
     pub fn new(depth: i8, dir: NewTaskDirection) -> NewTaskState {
         NewTaskState {
-            title: String::new(),
+            input: Input::default(),
             depth,
             dir,
         }
     }
 }
 
+/// Where a new task is entered relative to the selected task.
 #[derive(Debug, Clone)]
 pub enum NewTaskDirection {
     Above,
     Below,
 }
 
+/// The state of the application when editing the title of an existing task.
 #[derive(Debug, Clone)]
 pub struct EditTaskState {
     pub(crate) pos: usize,
-    pub(crate) title: String,
+    pub(crate) input: Input,
 }
 
 impl EditTaskState {
-    pub fn new(pos: usize, title: String) -> EditTaskState {
-        EditTaskState { pos, title }
+    pub fn new(pos: usize, title: String, cursor: usize) -> EditTaskState {
+        EditTaskState {
+            pos,
+            input: Input::default().with_value(title).with_cursor(cursor),
+        }
     }
 }
 
@@ -68,3 +184,53 @@ impl TaskSelectionState {
         TaskSelectionState { pos_start, pos_end }
     }
 }
+
+/// A field of a task that can be edited from the detail pane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetailField {
+    Description,
+    Status,
+    ExecutionOrder,
+    StartDate,
+    DueDate,
+    ExpectedDuration,
+}
+
+impl DetailField {
+    /// All the editable fields, in the order they are shown in the detail pane.
+    pub const ALL: [DetailField; 6] = [
+        DetailField::Description,
+        DetailField::Status,
+        DetailField::ExecutionOrder,
+        DetailField::StartDate,
+        DetailField::DueDate,
+        DetailField::ExpectedDuration,
+    ];
+}
+
+/// The state of the application when the detail pane has focus.
+#[derive(Debug, Clone)]
+pub struct TaskDetailState {
+    /// The field that is currently selected.
+    pub(crate) field: DetailField,
+    /// The input for the field, if it is currently being edited.
+    pub(crate) input: Option<Input>,
+    /// The editor for the description, if it is currently being edited.
+    pub(crate) editor: Option<TextArea>,
+    /// The number of rows that the description is scrolled down by.
+    pub(crate) description_scroll: u16,
+    /// An error from the last attempt to change the field.
+    pub(crate) error: Option<String>,
+}
+
+impl Default for TaskDetailState {
+    fn default() -> TaskDetailState {
+        TaskDetailState {
+            field: DetailField::Description,
+            input: None,
+            editor: None,
+            description_scroll: 0,
+            error: None,
+        }
+    }
+}
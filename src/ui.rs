@@ -1,2 +1,6 @@
 ﻿pub mod joiner;
+pub mod format;
 pub mod help;
+pub mod input;
+pub mod markdown;
+pub mod text_area;
@@ -0,0 +1,178 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeDelta, Weekday};
+
+/// The number of minutes in a working day.
+const MINUTES_PER_DAY: i32 = 8 * 60;
+
+/// Formats a duration in minutes as hours and minutes, e.g. `1h 30m`.
+pub fn format_duration(minutes: i32) -> String {
+    let sign = if minutes < 0 { "-" } else { "" };
+    let minutes = minutes.abs();
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{}{}m", sign, minutes),
+        (hours, 0) => format!("{}{}h", sign, hours),
+        (hours, minutes) => format!("{}{}h {}m", sign, hours, minutes),
+    }
+}
+
+/// Parses a duration into minutes, accepting forms such as `90`, `90m`, `1h30`, `1.5h` and `2d`.
+/// A day is a working day of eight hours. Returns None if the duration is not valid.
+pub fn parse_duration(value: &str) -> Option<i32> {
+    let value = value.trim().to_lowercase();
+    let mut chars = value.chars().filter(|c| !c.is_whitespace()).peekable();
+    let mut minutes = 0.0;
+    let mut last_unit = None;
+
+    while chars.peek().is_some() {
+        let number: String =
+            std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit() || *c == '.')).collect();
+        let unit: String = std::iter::from_fn(|| chars.next_if(|c| c.is_alphabetic())).collect();
+        let number: f64 = number.parse().ok()?;
+
+        let unit = match unit.chars().next() {
+            Some(unit) => unit,
+            // A number after hours is minutes, as is a number on its own
+            None if last_unit.is_none_or(|unit| unit == 'h') => 'm',
+            None => return None,
+        };
+        minutes += number
+            * match unit {
+                'd' => MINUTES_PER_DAY as f64,
+                'h' => 60.0,
+                'm' => 1.0,
+                _ => return None,
+            };
+        last_unit = Some(unit);
+    }
+
+    last_unit.map(|_| minutes.round() as i32)
+}
+
+/// Formats the time elapsed on a running timer as hours, minutes and seconds, e.g. `1:02:03`.
+pub fn format_elapsed(elapsed: TimeDelta) -> String {
+    let seconds = elapsed.num_seconds().max(0);
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{}:{:02}:{:02}", hours, seconds / 60 % 60, seconds % 60),
+    }
+}
+
+/// Parses a date entered as `today`, `tomorrow`, a weekday such as `fri`, an offset from today
+/// such as `+3d` or `+2w`, or a date in the form `YYYY-MM-DD`. A weekday is the next such day
+/// after today. Returns None if the date is not valid.
+pub fn parse_date(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let value = value.trim().to_lowercase();
+    match value.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.checked_add_days(Days::new(1)),
+        "yesterday" => return today.checked_sub_days(Days::new(1)),
+        _ => {}
+    }
+
+    if let Ok(weekday) = value.parse::<Weekday>() {
+        let days =
+            (weekday.num_days_from_monday() + 6 - today.weekday().num_days_from_monday()) % 7 + 1;
+        return today.checked_add_days(Days::new(days as u64));
+    }
+
+    if let Some(offset) = value.strip_prefix('+').or_else(|| value.strip_prefix('-')) {
+        let (number, days_per_unit) = match offset.strip_suffix(['d', 'w']) {
+            Some(number) if offset.ends_with('w') => (number, 7),
+            Some(number) => (number, 1),
+            None => return None,
+        };
+        let days = number.parse::<u64>().ok()? * days_per_unit;
+        return match value.starts_with('-') {
+            true => today.checked_sub_days(Days::new(days)),
+            false => today.checked_add_days(Days::new(days)),
+        };
+    }
+
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok()
+}
+
+/// Formats a date relative to today, e.g. `today`, `tomorrow`, `in 3d` or `2d ago`.
+pub fn format_relative_date(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => String::from("today"),
+        1 => String::from("tomorrow"),
+        -1 => String::from("yesterday"),
+        days if days > 0 => format!("in {}d", days),
+        days => format!("{}d ago", -days),
+    }
+}
+
+/// Formats the date part of a date and time.
+pub fn format_date(date: &DateTime<Local>) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Formats a date and time to the minute.
+pub fn format_date_time(date: &DateTime<Local>) -> String {
+    date.format("%Y-%m-%d %H:%M").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_duration, format_elapsed, format_relative_date, parse_date, parse_duration,
+    };
+    use chrono::{NaiveDate, TimeDelta};
+
+    #[test]
+    fn format_duration_uses_hours_and_minutes() {
+        assert_eq!(format_duration(45), "45m");
+        assert_eq!(format_duration(120), "2h");
+        assert_eq!(format_duration(90), "1h 30m");
+        assert_eq!(format_duration(-90), "-1h 30m");
+    }
+
+    #[test]
+    fn parse_duration_accepts_units() {
+        assert_eq!(parse_duration("90"), Some(90));
+        assert_eq!(parse_duration("90m"), Some(90));
+        assert_eq!(parse_duration("1h30"), Some(90));
+        assert_eq!(parse_duration("1h 30m"), Some(90));
+        assert_eq!(parse_duration("1.5h"), Some(90));
+        assert_eq!(parse_duration("2d"), Some(960));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("1d2"), None);
+        assert_eq!(parse_duration("soon"), None);
+    }
+
+    #[test]
+    fn parse_date_accepts_natural_language() {
+        // A Wednesday
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2026, 10, day);
+        assert_eq!(parse_date("today", today), date(14));
+        assert_eq!(parse_date("Tomorrow", today), date(15));
+        assert_eq!(parse_date("fri", today), date(16));
+        assert_eq!(parse_date("wed", today), date(21));
+        assert_eq!(parse_date("+3d", today), date(17));
+        assert_eq!(parse_date("+1w", today), date(21));
+        assert_eq!(
+            parse_date("2026-11-02", today),
+            NaiveDate::from_ymd_opt(2026, 11, 2)
+        );
+        assert_eq!(parse_date("someday", today), None);
+        assert_eq!(parse_date("+3x", today), None);
+        assert_eq!(parse_date("+é", today), None);
+        assert_eq!(parse_date("+3é", today), None);
+    }
+
+    #[test]
+    fn format_relative_date_counts_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+        assert_eq!(format_relative_date(date(14), today), "today");
+        assert_eq!(format_relative_date(date(15), today), "tomorrow");
+        assert_eq!(format_relative_date(date(20), today), "in 6d");
+        assert_eq!(format_relative_date(date(11), today), "3d ago");
+    }
+
+    #[test]
+    fn format_elapsed_shows_seconds() {
+        assert_eq!(format_elapsed(TimeDelta::seconds(65)), "1:05");
+        assert_eq!(format_elapsed(TimeDelta::seconds(3723)), "1:02:03");
+    }
+}
@@ -19,6 +19,9 @@ pub fn render_help(area: Rect, buf: &mut Buffer) {
     let key_maps = [
         KeyMap::global_key_commands_default(),
         KeyMap::task_list_key_commands_default(),
+        KeyMap::selection_key_commands_default(),
+        KeyMap::detail_key_commands_default(),
+        KeyMap::today_key_commands_default(),
     ];
 
     let key_map_lines = key_maps
@@ -42,7 +45,10 @@ impl KeyMap {
             commands: HashMap::new(),
         };
         map.insert_command("q", "Quit the application")
-            .insert_command("?", "Toggle the help menu");
+            .insert_command("?", "Toggle the help menu")
+            .insert_command("p", "Toggle the task detail pane")
+            .insert_command("w", "Switch between the tree and Today views")
+            .insert_command("R", "Show or hide the report of estimate overruns");
         map
     }
 
@@ -51,10 +57,16 @@ impl KeyMap {
             title: String::from("Task list commands"),
             commands: HashMap::new(),
         };
-        map.insert_command("j", "Go up one task")
-            .insert_command("k", "Go down one task")
-            .insert_command("h", "Go up one level")
-            .insert_command("l", "Go down one level")
+        map.insert_command("j", "Go down one task")
+            .insert_command("k", "Go up one task")
+            .insert_command("h", "Go to the parent task")
+            .insert_command("l", "Go to the first subtask")
+            .insert_command("J", "Go to the next sibling task")
+            .insert_command("K", "Go to the previous sibling task")
+            .insert_command("gg", "Go to the first task")
+            .insert_command("G", "Go to the last task")
+            .insert_command("CTRL+d", "Go down half a page")
+            .insert_command("CTRL+u", "Go up half a page")
             .insert_command("i", "Edit the task title from the beginning")
             .insert_command("a", "Edit the task title from the end")
             .insert_command("c", "Delete the task title and start editing")
@@ -62,12 +74,81 @@ impl KeyMap {
             .insert_command("O", "Add a new task above")
             .insert_command("d", "Delete the current task")
             .insert_command("v", "Enter selection mode.")
+            .insert_command("e", "Edit the task in the detail pane")
+            .insert_command("s", "Start the task and its timer")
+            .insert_command("z", "Pause the timer of the task")
+            .insert_command("S", "Complete the task and stop its timer")
+            .insert_command("n", "Edit the description of the task")
+            .insert_command("m", "Edit the estimate of the task")
+            .insert_command("f", "Fold or unfold the subtasks of the task")
+            .insert_command("F", "Fold all tasks")
+            .insert_command("E", "Unfold all tasks")
+            .insert_command("1-9", "Fold the tasks below the given depth")
+            .insert_command("b", "Switch between the tree and the timeline")
+            .insert_command("+", "Zoom the timeline in")
+            .insert_command("-", "Zoom the timeline out")
+            .insert_command("u", "Undo the last change")
+            .insert_command("CTRL+r", "Redo the last undone change")
+            .insert_command("ALT+j", "Move the task down past its sibling")
+            .insert_command("ALT+k", "Move the task up past its sibling")
             .insert_command("TAB", "Move the task down one level")
             .insert_command("SHIFT+TAB", "Move the task up one level")
             .insert_command("SPACE", "Change the task status");
         map
     }
 
+    pub fn selection_key_commands_default() -> KeyMap {
+        let mut map = KeyMap {
+            title: String::from("Selection commands"),
+            commands: HashMap::new(),
+        };
+        map.insert_command("j", "Extend the selection down")
+            .insert_command("k", "Extend the selection up")
+            .insert_command("d", "Delete the selected tasks")
+            .insert_command("t", "Change the execution order of the selected tasks")
+            .insert_command("ALT+j", "Move the selected tasks down")
+            .insert_command("ALT+k", "Move the selected tasks up")
+            .insert_command("TAB", "Move the selected tasks down one level")
+            .insert_command("SHIFT+TAB", "Move the selected tasks up one level")
+            .insert_command("SPACE", "Change the status of the selected tasks")
+            .insert_command("ESC", "Leave selection mode");
+        map
+    }
+
+    pub fn today_key_commands_default() -> KeyMap {
+        let mut map = KeyMap {
+            title: String::from("Today view commands"),
+            commands: HashMap::new(),
+        };
+        map.insert_command("j", "Go down one task")
+            .insert_command("k", "Go up one task")
+            .insert_command("SPACE", "Change the status of the task")
+            .insert_command("s", "Start the task and its timer")
+            .insert_command("z", "Pause the timer of the task")
+            .insert_command("c", "Complete the task and stop its timer")
+            .insert_command("u", "Undo the last change")
+            .insert_command("ENTER", "Show the task in the tree view");
+        map
+    }
+
+    pub fn detail_key_commands_default() -> KeyMap {
+        let mut map = KeyMap {
+            title: String::from("Detail pane commands"),
+            commands: HashMap::new(),
+        };
+        map.insert_command("j", "Go to the next field")
+            .insert_command("k", "Go to the previous field")
+            .insert_command("J", "Scroll the description down")
+            .insert_command("K", "Scroll the description up")
+            .insert_command("ENTER", "Edit or change the field")
+            .insert_command("CTRL+C", "Discard changes to the description")
+            .insert_command(
+                "ESC",
+                "Save the description, cancel the edit or leave the pane",
+            );
+        map
+    }
+
     fn insert_command(&mut self, key: &str, description: &str) -> &mut Self {
         self.commands
             .insert(key.to_string(), description.to_string());
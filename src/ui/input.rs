@@ -0,0 +1,66 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{buffer::Buffer, layout::Rect, style::Stylize, text::Span};
+use tui_input::{Input, InputRequest};
+
+/// Converts a key event into a request for a text input, or None if the key does not edit text.
+pub fn to_input_request(key_event: &KeyEvent) -> Option<InputRequest> {
+    let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key_event.modifiers.contains(KeyModifiers::ALT);
+
+    let request = match key_event.code {
+        KeyCode::Backspace if ctrl || alt => InputRequest::DeletePrevWord,
+        KeyCode::Backspace => InputRequest::DeletePrevChar,
+        KeyCode::Delete if ctrl || alt => InputRequest::DeleteNextWord,
+        KeyCode::Delete => InputRequest::DeleteNextChar,
+        KeyCode::Left if ctrl || alt => InputRequest::GoToPrevWord,
+        KeyCode::Left => InputRequest::GoToPrevChar,
+        KeyCode::Right if ctrl || alt => InputRequest::GoToNextWord,
+        KeyCode::Right => InputRequest::GoToNextChar,
+        KeyCode::Home => InputRequest::GoToStart,
+        KeyCode::End => InputRequest::GoToEnd,
+        KeyCode::Char('a') if ctrl => InputRequest::GoToStart,
+        KeyCode::Char('e') if ctrl => InputRequest::GoToEnd,
+        KeyCode::Char('b') if ctrl => InputRequest::GoToPrevChar,
+        KeyCode::Char('f') if ctrl => InputRequest::GoToNextChar,
+        KeyCode::Char('b') if alt => InputRequest::GoToPrevWord,
+        KeyCode::Char('f') if alt => InputRequest::GoToNextWord,
+        KeyCode::Char('w') if ctrl => InputRequest::DeletePrevWord,
+        KeyCode::Char('d') if alt => InputRequest::DeleteNextWord,
+        KeyCode::Char('u') if ctrl => InputRequest::DeleteLine,
+        KeyCode::Char(c) if !ctrl && !alt => InputRequest::InsertChar(c),
+        _ => return None,
+    };
+    Some(request)
+}
+
+/// Renders a single line text input, highlighting the cursor and scrolling the text horizontally
+/// so that the cursor is always visible.
+pub fn render_input(input: &Input, area: Rect, buf: &mut Buffer) {
+    if area.width == 0 {
+        return;
+    }
+
+    let width = area.width as usize;
+    let cursor = input.cursor();
+    // Leave room for the cursor at the end of the text
+    let scroll = (cursor + 1).saturating_sub(width);
+
+    buf.set_span(
+        area.x,
+        area.y,
+        &Span::from(" ".repeat(width)).on_dark_gray(),
+        area.width,
+    );
+    let value: String = input.value().chars().skip(scroll).take(width).collect();
+    buf.set_span(
+        area.x,
+        area.y,
+        &Span::from(value).on_dark_gray(),
+        area.width,
+    );
+
+    let cursor_x = area.x + (cursor - scroll) as u16;
+    if let Some(cell) = buf.cell_mut((cursor_x, area.y)) {
+        cell.set_style(cell.style().reversed());
+    }
+}
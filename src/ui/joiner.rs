@@ -1,23 +1,38 @@
 ﻿use crate::task::ExecutionOrder;
 use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
 use ratatui::text::Span;
 
-/// Renders a new joiner starting at the specified location and connecting the positions given in `subtask_coords`
+/// Renders a new joiner starting at the specified location and connecting the positions given in `subtask_coords`.
+/// The y coordinates may lie outside of `area`, in which case only the part of the joiner inside the area is rendered.
 pub fn render_joiner(
     mut x: u16,
-    mut y: u16,
-    subtask_coords: Vec<u16>,
+    mut y: i32,
+    subtask_coords: Vec<i32>,
+    area: Rect,
     buf: &mut Buffer,
     execution_order: &ExecutionOrder,
 ) {
+    // Only draw the parts of the joiner that fall within the area
+    let mut set_span = |x: u16, y: i32, symbol: &str| {
+        if y >= area.top() as i32 && y < area.bottom() as i32 {
+            buf.set_span(
+                x,
+                y as u16,
+                &Span::from(symbol),
+                symbol.chars().count() as u16,
+            );
+        }
+    };
+
     x += 1;
     // Render the first part of the joiner
-    buf.set_span(x, y, &Span::from("╮"), 1);
+    set_span(x, y, "╮");
     y += 1;
     match execution_order {
         ExecutionOrder::Series => {
             // Connect first joiner
-            buf.set_span(x, y, &Span::from("╰─"), 2);
+            set_span(x, y, "╰─");
             // Increment x to align with next tasks
             x += 2;
 
@@ -28,28 +43,30 @@ pub fn render_joiner(
             // Connect the rest of the subtasks if there is a space between them
             let mut y_current = y + 1;
             for y_next in &subtask_coords[1..] {
-                for y_cursor in y_current..*y_next {
-                    buf.set_span(x, y_cursor, &Span::from("│"), 1);
+                for y_cursor in
+                    y_current.max(area.top() as i32 - 1)..(*y_next).min(area.bottom() as i32)
+                {
+                    set_span(x, y_cursor, "│");
                 }
                 y_current = y_next + 1;
             }
         }
         ExecutionOrder::Parallel => {
             // Connect to last subtask
-            let Some(y_last) = subtask_coords.last() else {
+            let Some(&y_last) = subtask_coords.last() else {
                 return;
             };
-            buf.set_span(x, *y_last, &Span::from("╰─"), 2);
+            set_span(x, y_last, "╰─");
             // Draw a vertical connector all the way to the last subtask
-            for y_cursor in y..*y_last {
-                buf.set_span(x, y_cursor, &Span::from("│"), 1);
+            for y_cursor in y.max(area.top() as i32 - 1)..y_last.min(area.bottom() as i32) {
+                set_span(x, y_cursor, "│");
             }
             // Go back and draw intermediate connectors
             if subtask_coords.len() <= 1 {
                 return;
             }
-            for y_cursor in &subtask_coords[..subtask_coords.len() - 1] {
-                buf.set_span(x, *y_cursor, &Span::from("├─"), 2);
+            for &y_cursor in &subtask_coords[..subtask_coords.len() - 1] {
+                set_span(x, y_cursor, "├─");
             }
         }
     }
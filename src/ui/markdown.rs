@@ -0,0 +1,155 @@
+use ratatui::{
+    style::{Style, Stylize},
+    text::{Line, Span},
+};
+
+/// Converts Markdown text into styled lines. Only a basic subset is supported: headings, bullet
+/// lists, task checkboxes, block quotes, code blocks, and inline bold, italic and code spans.
+pub fn markdown_lines(text: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Line::from(line.to_string()).cyan());
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        let line = line.trim_start();
+        let prefix = " ".repeat(indent);
+
+        let heading = line.chars().take_while(|&c| c == '#').count();
+        if heading > 0 && line[heading..].starts_with(' ') {
+            let style = Style::default().bold();
+            let style = match heading {
+                1 => style.underlined(),
+                _ => style,
+            };
+            lines.push(Line::from(inline_spans(line[heading..].trim(), style)));
+        } else if let Some(rest) = line.strip_prefix("> ") {
+            let mut spans = vec![Span::from(format!("{}│ ", prefix)).dark_gray()];
+            spans.extend(inline_spans(rest, Style::default().italic()));
+            lines.push(Line::from(spans));
+        } else if let Some(rest) = strip_bullet(line) {
+            let (marker, rest) = match rest {
+                _ if rest.starts_with("[ ] ") => ("☐ ", &rest[4..]),
+                _ if rest.starts_with("[x] ") || rest.starts_with("[X] ") => ("☑ ", &rest[4..]),
+                _ => ("• ", rest),
+            };
+            let mut spans = vec![Span::from(format!("{}{}", prefix, marker))];
+            spans.extend(inline_spans(rest, Style::default()));
+            lines.push(Line::from(spans));
+        } else {
+            let mut spans = vec![Span::from(prefix)];
+            spans.extend(inline_spans(line, Style::default()));
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+/// Gets the text of a bullet list item after its marker, or None if the line is not a bullet.
+fn strip_bullet(line: &str) -> Option<&str> {
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+}
+
+/// Splits a line into spans styled by its inline `code`, **bold** and *italic* markers.
+fn inline_spans(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut bold = false;
+    let mut italic = false;
+    let mut rest = text;
+
+    let style = |bold: bool, italic: bool| {
+        let style = if bold { base.bold() } else { base };
+        if italic {
+            style.italic()
+        } else {
+            style
+        }
+    };
+
+    while let Some(c) = rest.chars().next() {
+        let marker = if rest.starts_with("**") || rest.starts_with("__") {
+            Some(2)
+        } else if c == '*' || c == '_' || c == '`' {
+            Some(1)
+        } else {
+            None
+        };
+
+        match marker {
+            Some(_) if c == '`' => {
+                // Code spans are shown as written up to the closing backtick
+                if let Some(end) = rest[1..].find('`') {
+                    spans.push(Span::styled(
+                        std::mem::take(&mut current),
+                        style(bold, italic),
+                    ));
+                    spans.push(Span::from(rest[1..end + 1].to_string()).cyan());
+                    rest = &rest[end + 2..];
+                    continue;
+                }
+                current.push(c);
+                rest = &rest[1..];
+            }
+            // Underscores inside words are part of the word rather than emphasis
+            Some(1) if c == '_' && !current.is_empty() && !current.ends_with(' ') => {
+                current.push(c);
+                rest = &rest[1..];
+            }
+            Some(len) => {
+                spans.push(Span::styled(
+                    std::mem::take(&mut current),
+                    style(bold, italic),
+                ));
+                if len == 2 {
+                    bold = !bold;
+                } else {
+                    italic = !italic;
+                }
+                rest = &rest[len..];
+            }
+            None => {
+                current.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    spans.push(Span::styled(current, style(bold, italic)));
+    spans.retain(|span| !span.content.is_empty());
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::markdown_lines;
+    use ratatui::style::Modifier;
+
+    #[test]
+    fn markdown_is_styled() {
+        let lines = markdown_lines("# Notes\n- [x] **done** and `code`\nplain_text");
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].to_string(), "Notes");
+        assert!(lines[0].spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+
+        assert_eq!(lines[1].to_string(), "☑ done and code");
+        let done = &lines[1].spans[1];
+        assert_eq!(done.content, "done");
+        assert!(done.style.add_modifier.contains(Modifier::BOLD));
+
+        assert_eq!(lines[2].to_string(), "plain_text");
+    }
+}
@@ -0,0 +1,215 @@
+use crate::ui::input::to_input_request;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{buffer::Buffer, layout::Rect, style::Stylize, text::Span};
+use tui_input::Input;
+
+/// A multi-line text editor that wraps long lines at word boundaries and scrolls vertically to
+/// keep the cursor in view.
+#[derive(Debug, Clone, Default)]
+pub struct TextArea {
+    lines: Vec<String>,
+    /// The line that the cursor is on.
+    row: usize,
+    /// The character within the line that the cursor is before.
+    col: usize,
+    /// The number of wrapped rows that the text is scrolled down by.
+    scroll: usize,
+}
+
+impl TextArea {
+    /// Creates an editor containing the text, with the cursor at the end.
+    pub fn new(text: &str) -> TextArea {
+        let lines: Vec<String> = text.split('\n').map(String::from).collect();
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        TextArea {
+            lines,
+            row,
+            col,
+            scroll: 0,
+        }
+    }
+
+    /// Gets the text in the editor, with lines separated by newlines.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Handles a key press that edits the text or moves the cursor.
+    pub fn handle_key(&mut self, key_event: &KeyEvent) {
+        match key_event.code {
+            KeyCode::Enter => {
+                let rest = split_off_chars(&mut self.lines[self.row], self.col);
+                self.lines.insert(self.row + 1, rest);
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Up => self.move_row(-1),
+            KeyCode::Down => self.move_row(1),
+            // Moving past the ends of a line continues on the neighbouring line
+            KeyCode::Left if self.col == 0 && self.row > 0 => {
+                self.row -= 1;
+                self.col = self.line_len();
+            }
+            KeyCode::Right if self.col == self.line_len() && self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = 0;
+            }
+            // Deleting past the ends of a line joins it to the neighbouring line
+            KeyCode::Backspace if self.col == 0 && self.row > 0 => {
+                let line = self.lines.remove(self.row);
+                self.row -= 1;
+                self.col = self.line_len();
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Delete if self.col == self.line_len() && self.row + 1 < self.lines.len() => {
+                let line = self.lines.remove(self.row + 1);
+                self.lines[self.row].push_str(&line);
+            }
+            _ => self.edit_line(key_event),
+        }
+    }
+
+    /// Edits the current line as a single line input.
+    fn edit_line(&mut self, key_event: &KeyEvent) {
+        let Some(request) = to_input_request(key_event) else {
+            return;
+        };
+        let mut input = Input::default()
+            .with_value(self.lines[self.row].clone())
+            .with_cursor(self.col);
+        input.handle(request);
+        self.lines[self.row] = input.value().to_string();
+        self.col = input.cursor();
+    }
+
+    /// Moves the cursor up or down by a number of lines, keeping it within the line.
+    fn move_row(&mut self, change: isize) {
+        let row = self.row.saturating_add_signed(change);
+        self.row = row.min(self.lines.len() - 1);
+        self.col = self.col.min(self.line_len());
+    }
+
+    /// Gets the number of characters in the current line.
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    /// Renders the text into the area, scrolling so that the cursor stays in view.
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+        let width = area.width as usize;
+        let height = area.height as usize;
+
+        // Break every line into wrapped rows and find the row that holds the cursor
+        let mut rows: Vec<String> = Vec::new();
+        let mut cursor = (0, 0);
+        for (row, line) in self.lines.iter().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            let starts = wrap_line(&chars, width);
+            for (i, &start) in starts.iter().enumerate() {
+                let end = starts.get(i + 1).copied().unwrap_or(chars.len());
+                let last = i + 1 == starts.len();
+                if row == self.row && self.col >= start && (self.col < end || last) {
+                    cursor = (rows.len(), self.col - start);
+                }
+                rows.push(chars[start..end].iter().collect());
+            }
+        }
+
+        // Scroll just far enough to bring the cursor into view
+        let (cursor_row, cursor_col) = cursor;
+        if cursor_row < self.scroll {
+            self.scroll = cursor_row;
+        }
+        if cursor_row >= self.scroll + height {
+            self.scroll = cursor_row + 1 - height;
+        }
+
+        for y in area.top()..area.bottom() {
+            buf.set_span(
+                area.x,
+                y,
+                &Span::from(" ".repeat(width)).on_dark_gray(),
+                area.width,
+            );
+        }
+        for (i, row) in rows.iter().skip(self.scroll).take(height).enumerate() {
+            let y = area.y + i as u16;
+            buf.set_span(
+                area.x,
+                y,
+                &Span::from(row.as_str()).on_dark_gray(),
+                area.width,
+            );
+        }
+
+        let x = area.x + (cursor_col as u16).min(area.width - 1);
+        let y = area.y + (cursor_row - self.scroll) as u16;
+        if let Some(cell) = buf.cell_mut((x, y)) {
+            cell.set_style(cell.style().reversed());
+        }
+    }
+}
+
+/// Splits a string at a character index, returning the part after the index.
+fn split_off_chars(line: &mut String, index: usize) -> String {
+    let byte = line
+        .char_indices()
+        .nth(index)
+        .map_or(line.len(), |(byte, _)| byte);
+    line.split_off(byte)
+}
+
+/// Gets the index of the first character of every row that a line is wrapped into. Lines are
+/// broken after the last space that fits in the width, or at the width if a word is too long.
+fn wrap_line(chars: &[char], width: usize) -> Vec<usize> {
+    let mut starts = vec![0];
+    let mut start = 0;
+    // Leave room for the cursor after the last character of a full row
+    while chars.len() - start >= width {
+        let end = start + width;
+        let space = chars[start..end].iter().rposition(|&c| c == ' ');
+        start = match space {
+            Some(space) if space > 0 => start + space + 1,
+            _ => end,
+        };
+        starts.push(start);
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wrap_line, TextArea};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn press(text_area: &mut TextArea, code: KeyCode) {
+        text_area.handle_key(&KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn enter_and_backspace_split_and_join_lines() {
+        let mut text_area = TextArea::new("first line");
+        for _ in 0..5 {
+            press(&mut text_area, KeyCode::Left);
+        }
+        press(&mut text_area, KeyCode::Enter);
+        press(&mut text_area, KeyCode::Char('-'));
+        assert_eq!(text_area.text(), "first\n- line");
+
+        press(&mut text_area, KeyCode::Backspace);
+        press(&mut text_area, KeyCode::Backspace);
+        assert_eq!(text_area.text(), "first line");
+    }
+
+    #[test]
+    fn lines_wrap_at_spaces() {
+        let chars: Vec<char> = "one two three".chars().collect();
+        assert_eq!(wrap_line(&chars, 8), vec![0, 8]);
+        let chars: Vec<char> = "abcdefghij".chars().collect();
+        assert_eq!(wrap_line(&chars, 4), vec![0, 4, 8]);
+    }
+}